@@ -0,0 +1,153 @@
+#!/usr/bin/env python3
+"""Checks the signing vectors pinned in src/signature and src/exchange against the official
+Python SDK (hyperliquid-python-sdk, hyperliquid/utils/signing.py).
+
+    pip install hyperliquid-python-sdk
+    python3 scripts/signing_vectors.py
+
+Every vector is computed with the SDK's own `action_hash`, `sign_l1_action` and
+`sign_user_signed_action`, and the SDK version is printed so it can be recorded next to
+the vectors in the Rust tests.
+"""
+
+from importlib.metadata import version
+
+from eth_account import Account
+from hyperliquid.utils.signing import (
+    SEND_ASSET_SIGN_TYPES,
+    TOKEN_DELEGATE_TYPES,
+    USD_CLASS_TRANSFER_SIGN_TYPES,
+    action_hash,
+    sign_l1_action,
+    sign_user_signed_action,
+)
+
+WALLET = Account.from_key("0xe908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e")
+NONCE = 1690393044548
+
+ORDER_ACTION = {
+    "type": "order",
+    "orders": [{"a": 1, "b": True, "p": "2000.0", "s": "3.5", "r": False, "t": {"limit": {"tif": "Ioc"}}}],
+    "grouping": "na",
+}
+ORDER_NONCE = 1583838
+ORDER_EXPIRES_AFTER = ORDER_NONCE + 60_000
+VAULT_ADDRESS = "0x1719884eb866cb12b2287399b15f7db5e7d775ea"
+
+# The staking actions are signed through `sign_user_signed_action` like `tokenDelegate`,
+# with these structs
+C_DEPOSIT_SIGN_TYPES = [
+    {"name": "hyperliquidChain", "type": "string"},
+    {"name": "wei", "type": "uint64"},
+    {"name": "nonce", "type": "uint64"},
+]
+C_WITHDRAW_SIGN_TYPES = C_DEPOSIT_SIGN_TYPES
+
+
+def hex_signature(signature):
+    return f"{signature['r'][2:]:0>64}{signature['s'][2:]:0>64}{signature['v']:02x}"
+
+
+def user_signed(action, sign_types, primary_type):
+    return hex_signature(sign_user_signed_action(WALLET, action, sign_types, primary_type, False))
+
+
+def order(vault_address):
+    return hex_signature(sign_l1_action(WALLET, ORDER_ACTION, vault_address, ORDER_NONCE, ORDER_EXPIRES_AFTER, True))
+
+
+# Each vector with the value pinned in the Rust tests
+VECTORS = [
+    (
+        "usdClassTransfer (create_signature.rs)",
+        lambda: user_signed(
+            {"type": "usdClassTransfer", "amount": "1", "toPerp": True, "nonce": NONCE},
+            USD_CLASS_TRANSFER_SIGN_TYPES,
+            "HyperliquidTransaction:UsdClassTransfer",
+        ),
+        "f77964893919a5436f79bbadf5bf4d8b257efcca056dfd10b1ede57819b8ff9130289a50a4b08d01993f7a7435d88933f9021885a3c685d0b1b1094a5d461c381b",
+    ),
+    (
+        "sendAsset (create_signature.rs)",
+        lambda: user_signed(
+            {
+                "type": "sendAsset",
+                "destination": "0x0d1d9635d0640821d15e323ac8adadfa9c111414",
+                "sourceDex": "",
+                "destinationDex": "spot",
+                "token": "USDC",
+                "amount": "1",
+                "fromSubAccount": "",
+                "nonce": NONCE,
+            },
+            SEND_ASSET_SIGN_TYPES,
+            "HyperliquidTransaction:SendAsset",
+        ),
+        "eb96384f3ebc9746f655ef90d54f3e239927cd05e0364dda376024b60a987c253813fcf0b1fe0f6e347aab0c51977a526d3ad792d683c512e6716476d0c670551c",
+    ),
+    (
+        "cDeposit (create_signature.rs)",
+        lambda: user_signed(
+            {"type": "cDeposit", "wei": 100_000_000, "nonce": NONCE},
+            C_DEPOSIT_SIGN_TYPES,
+            "HyperliquidTransaction:CDeposit",
+        ),
+        "236016598893e096f334bc60e1e70ad73558b073f439852a703617725fecf42535d704febfb519dd41f47d889899504fb10dee1dc1183131c936b5546159994b1b",
+    ),
+    (
+        "cWithdraw (create_signature.rs)",
+        lambda: user_signed(
+            {"type": "cWithdraw", "wei": 100_000_000, "nonce": NONCE},
+            C_WITHDRAW_SIGN_TYPES,
+            "HyperliquidTransaction:CWithdraw",
+        ),
+        "9cb3f555c1c4b9861fe4ee3a294add9cf9d9c362ef02e2e54c1735bf21d65574758a01f015666b7353d87f74e910a3080dabf31ee9b80dd4347b34f37f7c1c7f1b",
+    ),
+    (
+        "tokenDelegate (create_signature.rs)",
+        lambda: user_signed(
+            {
+                "type": "tokenDelegate",
+                "validator": "0x5ac99df645f3414876c816caa18b2d234024b487",
+                "wei": 100_000_000,
+                "isUndelegate": False,
+                "nonce": NONCE,
+            },
+            TOKEN_DELEGATE_TYPES,
+            "HyperliquidTransaction:TokenDelegate",
+        ),
+        "4ac2988821f24c19ab789fbe572d11f4b9b98fd98eef6f04f9ba858abb3494dc35458ac91367d27aca4a5819efcd1a20eb304c31ec9bf3f757270a84199dab101c",
+    ),
+    (
+        "order action_hash with expiresAfter (exchange_client.rs)",
+        lambda: action_hash(ORDER_ACTION, None, ORDER_NONCE, ORDER_EXPIRES_AFTER).hex(),
+        "f833db1e228f1a7717665cbff532260a4965bf8ff84683f86b230a7d1ea44a77",
+    ),
+    (
+        "order with expiresAfter, mainnet (exchange_client.rs)",
+        lambda: order(None),
+        "07de8aebc5176038fb9545d50c6a55ddbad58b6b70796eb8d07d672c14576fdc4d5f062d2ea51d421acc37fa88340e98864a68720c0e85be3e06bdedb107bfc81b",
+    ),
+    (
+        "order with vault and expiresAfter, mainnet (exchange_client.rs)",
+        lambda: order(VAULT_ADDRESS),
+        "3467006af497bdfa9c739e4780dd372927b78559c00bd2294430d065ca8502d553a7a200dd600a8664339e9887d6306e4d7c022c624c2a18ed01775ca0367e121b",
+    ),
+]
+
+
+def main():
+    print(f"hyperliquid-python-sdk {version('hyperliquid-python-sdk')}")
+    failed = False
+    for name, compute, pinned in VECTORS:
+        value = compute().removeprefix("0x")
+        if value == pinned:
+            print(f"ok {name}")
+        else:
+            failed = True
+            print(f"MISMATCH {name}\n  sdk    {value}\n  pinned {pinned}")
+    raise SystemExit(failed)
+
+
+if __name__ == "__main__":
+    main()
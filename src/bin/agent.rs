@@ -1,7 +1,14 @@
+use std::sync::Arc;
+
 use log::info;
 
 use ethers::signers::{LocalWallet, Signer};
-use hl_ranger::{BaseUrl, ClientLimit, ClientOrder, ClientOrderRequest, ExchangeClient};
+use hl_ranger::{
+    AgentKeystore, AgentManager, BaseUrl, ClientLimit, ClientOrder, ClientOrderRequest,
+    ExchangeClient, InfoClient,
+};
+
+const AGENT_NAME: &str = "market-maker";
 
 #[tokio::main]
 async fn main() {
@@ -10,23 +17,32 @@ async fn main() {
     let wallet: LocalWallet = "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e"
         .parse()
         .unwrap();
+    let password = std::env::var("AGENT_KEYSTORE_PASSWORD").unwrap_or("password".to_string());
 
     let exchange_client = ExchangeClient::new(None, wallet, Some(BaseUrl::Testnet), None, None)
         .await
         .unwrap();
+    let keystore = AgentKeystore::new("agents").unwrap();
+    let agent_manager = AgentManager::new(Arc::new(exchange_client), keystore.clone());
 
     /*
-        Create a new wallet with the agent.
+        Create a named agent, or replace it with a new key if it already exists.
         This agent cannot transfer or withdraw funds, but can for example place orders.
     */
+    let agent = if keystore.contains(AGENT_NAME) {
+        agent_manager.rotate(AGENT_NAME, &password).await.unwrap()
+    } else {
+        agent_manager.create(AGENT_NAME, &password).await.unwrap()
+    };
+    info!("Agent address: {:?}", agent.address());
 
-    let (private_key, response) = exchange_client.approve_agent(None).await.unwrap();
-    info!("Agent creation response: {response:?}");
-
-    let wallet: LocalWallet = private_key.parse().unwrap();
-
-    info!("Agent address: {:?}", wallet.address());
+    let info_client = InfoClient::new(None, Some(BaseUrl::Testnet)).await.unwrap();
+    for agent in agent_manager.list(&info_client).await.unwrap() {
+        info!("Approved agent: {agent:?}");
+    }
 
+    // A bot only needs the agent's name and the keystore password
+    let wallet = keystore.load(AGENT_NAME, &password).unwrap();
     let exchange_client = ExchangeClient::new(None, wallet, Some(BaseUrl::Testnet), None, None)
         .await
         .unwrap();
@@ -44,6 +60,8 @@ async fn main() {
     };
 
     let response = exchange_client.order(order, None).await.unwrap();
-
     info!("Order placed: {response:?}");
+
+    agent_manager.revoke(AGENT_NAME).await.unwrap();
+    info!("Agent {AGENT_NAME} revoked");
 }
@@ -3,9 +3,11 @@ This is an example of a basic market making strategy.
 
 We subscribe to the current mid price and build a market around this price. Whenever our market becomes outdated, we place and cancel orders to renew it.
 */
+use std::time::Duration;
+
 use ethers::signers::LocalWallet;
 
-use hl_ranger::{MarketMaker, MarketMakerInput};
+use hl_ranger::{MarketMaker, MarketMakerInput, ScheduleCancelConfig};
 
 #[tokio::main]
 async fn main() {
@@ -22,6 +24,14 @@ async fn main() {
         max_absolute_position_size: 0.5,
         decimals: 1,
         wallet,
+        schedule_cancel: Some(ScheduleCancelConfig {
+            heartbeat_timeout: Some(Duration::from_secs(30)),
+            ..Default::default()
+        }),
     };
-    MarketMaker::new(market_maker_input).await.start().await
+    MarketMaker::new(market_maker_input)
+        .await
+        .unwrap()
+        .start()
+        .await
 }
@@ -3,7 +3,6 @@ use log::info;
 
 use hl_ranger::{
     BaseUrl, ClientCancelRequest, ClientLimit, ClientOrder, ClientOrderRequest, ExchangeClient,
-    ExchangeDataStatus, ExchangeResponseStatus,
 };
 use std::{thread::sleep, time::Duration};
 
@@ -31,18 +30,11 @@ async fn main() {
         }),
     };
 
-    let response = exchange_client.order(order, None).await.unwrap();
-    info!("Order placed: {response:?}");
+    let result = exchange_client.order_result(order, None).await.unwrap();
+    info!("Order placed: {result:?}");
 
-    let response = match response {
-        ExchangeResponseStatus::Ok(exchange_response) => exchange_response,
-        ExchangeResponseStatus::Err(e) => panic!("error with exchange response: {e}"),
-    };
-    let status = response.data.unwrap().statuses[0].clone();
-    let oid = match status {
-        ExchangeDataStatus::Filled(order) => order.oid,
-        ExchangeDataStatus::Resting(order) => order.oid,
-        _ => panic!("Error: {status:?}"),
+    let Some(oid) = result.oid() else {
+        panic!("Error: {:?}", result.status);
     };
 
     // So you can see the order before it's cancelled
@@ -54,6 +46,9 @@ async fn main() {
     };
 
     // This response will return an error if order was filled (since you can't cancel a filled order), otherwise it will cancel the order
-    let response = exchange_client.cancel(cancel, None).await.unwrap();
-    info!("Order potentially cancelled: {response:?}");
+    let results = exchange_client
+        .bulk_cancel_results(vec![cancel], None)
+        .await
+        .unwrap();
+    info!("Order potentially cancelled: {:?}", results[0].result);
 }
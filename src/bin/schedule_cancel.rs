@@ -0,0 +1,40 @@
+use std::{sync::Arc, time::Duration};
+
+use ethers::signers::LocalWallet;
+
+use hl_ranger::{BaseUrl, DeadMansSwitch, ExchangeClient, ScheduleCancelConfig};
+use log::info;
+
+#[tokio::main]
+async fn main() {
+    env_logger::init();
+    // Key was randomly generated for testing and shouldn't be used with any real funds
+    let wallet: LocalWallet = "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e"
+        .parse()
+        .unwrap();
+
+    let exchange_client = ExchangeClient::new(None, wallet, Some(BaseUrl::Testnet), None, None)
+        .await
+        .unwrap();
+
+    // Cancel all open orders in 10 seconds
+    let time = exchange_client.nonce_manager.now_ms() + 10_000;
+    let res = exchange_client.schedule_cancel(Some(time), None).await;
+    if let Ok(res) = res {
+        info!("Exchange response: {res:#?}");
+    } else {
+        info!("Got error: {:#?}", res.err().unwrap());
+    }
+
+    // Keep pushing the cancel back while this process runs
+    let dead_mans_switch =
+        DeadMansSwitch::start(Arc::new(exchange_client), ScheduleCancelConfig::default()).unwrap();
+    tokio::time::sleep(Duration::from_secs(40)).await;
+
+    let res = dead_mans_switch.disarm().await;
+    if let Ok(res) = res {
+        info!("Exchange response: {res:#?}");
+    } else {
+        info!("Got error: {:#?}", res.err().unwrap());
+    }
+}
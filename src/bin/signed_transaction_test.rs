@@ -70,78 +70,13 @@ async fn sign_and_post_transaction(
         signature.r, signature.s, signature.v
     );
 
-    // The ExchangeClient's internal post method is not public.
-    // We need to replicate its functionality or use a similar public method if available.
-    // For now, let's use the existing `exchange_client.post` by making it pub(crate) or creating a helper.
-    // Looking at `ExchangeClient`, the `post` method is private.
-    // We will call it directly here.
-    // To do this, we need to construct the `ExchangePayload` manually and serialize it.
-
-    #[derive(serde::Serialize)]
-    #[serde(rename_all = "camelCase")]
-    struct ExchangePayloadInternal<'a> {
-        action: &'a serde_json::Value,
-        signature: Signature,
-        nonce: u64,
-        vault_address: Option<ethers::types::H160>,
-    }
-
-    let exchange_payload_internal = ExchangePayloadInternal {
-        action: &components.action_payload_json,
-        signature,
-        nonce: components.nonce,
-        vault_address: components.vault_address,
-    };
-
-    let payload_str = serde_json::to_string(&exchange_payload_internal)
-        .map_err(|e| hl_ranger::Error::JsonParse(e.to_string()))?;
-
-    info!("Sending payload: {}", payload_str);
-
-    let response_str = exchange_client
-        .http_client
-        .post("/exchange", payload_str)
+    exchange_client
+        .submit_signed(components, signature)
         .await
         .map_err(|e| {
-            error!("HTTP request failed: {}", e);
-            hl_ranger::Error::GenericRequest(e.to_string())
-        })?;
-
-    info!("Received response string: '{}'", response_str);
-    info!("Response length: {} bytes", response_str.len());
-    info!(
-        "Response bytes (first 200): {:?}",
-        response_str.chars().take(200).collect::<String>()
-    );
-
-    if response_str.is_empty() {
-        error!("Server returned empty response!");
-        return Err(hl_ranger::Error::GenericRequest(
-            "Server returned empty response".to_string(),
-        ));
-    }
-
-    // Try to parse the response, but provide more detailed error info if it fails
-    serde_json::from_str(&response_str).map_err(|e| {
-        error!("Failed to parse response JSON: {}", e);
-        error!("Raw response was: '{}'", response_str);
-        error!("Response as bytes: {:?}", response_str.as_bytes());
-        error!("Is valid UTF-8: {}", response_str.is_ascii());
-
-        // Try to identify what type of response this might be
-        if response_str.starts_with('<') {
-            error!("Response appears to be HTML (possibly an error page)");
-        } else if response_str.starts_with('{') || response_str.starts_with('[') {
-            error!("Response appears to be JSON but failed to parse");
-        } else {
-            error!("Response format is unknown");
-        }
-
-        hl_ranger::Error::JsonParse(format!(
-            "Failed to parse response: {}. Raw response: '{}'",
-            e, response_str
-        ))
-    })
+            error!("Submitting signed transaction failed: {}", e);
+            e
+        })
 }
 
 #[tokio::main]
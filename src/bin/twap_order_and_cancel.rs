@@ -0,0 +1,54 @@
+use ethers::signers::LocalWallet;
+use log::info;
+
+use hl_ranger::{
+    BaseUrl, ClientTwapCancelRequest, ClientTwapRequest, ExchangeClient, ExchangeDataStatus,
+    ExchangeResponseStatus,
+};
+use std::{thread::sleep, time::Duration};
+
+#[tokio::main]
+async fn main() {
+    env_logger::init();
+    // Key was randomly generated for testing and shouldn't be used with any real funds
+    let wallet: LocalWallet = "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e"
+        .parse()
+        .unwrap();
+
+    let exchange_client = ExchangeClient::new(None, wallet, Some(BaseUrl::Testnet), None, None)
+        .await
+        .unwrap();
+
+    let twap = ClientTwapRequest {
+        asset: "ETH".to_string(),
+        is_buy: true,
+        sz: 0.1,
+        reduce_only: false,
+        minutes: 30,
+        randomize: false,
+    };
+
+    let response = exchange_client.twap_order(twap, None).await.unwrap();
+    info!("TWAP placed: {response:?}");
+
+    let response = match response {
+        ExchangeResponseStatus::Ok(exchange_response) => exchange_response,
+        ExchangeResponseStatus::Err(e) => panic!("error with exchange response: {e}"),
+    };
+    let status = response.data.unwrap().status.unwrap();
+    let twap_id = match status {
+        ExchangeDataStatus::Running(twap) => twap.twap_id,
+        _ => panic!("Error: {status:?}"),
+    };
+
+    // So you can see the first slices before it's cancelled
+    sleep(Duration::from_secs(10));
+
+    let cancel = ClientTwapCancelRequest {
+        asset: "ETH".to_string(),
+        twap_id,
+    };
+
+    let response = exchange_client.twap_cancel(cancel, None).await.unwrap();
+    info!("TWAP cancelled: {response:?}");
+}
@@ -1,5 +1,8 @@
+use ethers::types::H160;
 use thiserror::Error;
 
+use crate::ExchangeError;
+
 #[derive(Error, Debug, Clone)]
 pub enum Error {
     // TODO: turn some embedded types into errors instead of strings
@@ -61,4 +64,12 @@ pub enum Error {
     SignatureFailure(String),
     #[error("Vault address not found")]
     VaultAddressNotFound,
+    #[error("Nonce error: {0:?}")]
+    Nonce(String),
+    #[error("IO error: {0:?}")]
+    Io(String),
+    #[error("Signature was produced by {recovered:?}, which may not sign for {user:?}")]
+    UnauthorizedSigner { user: H160, recovered: H160 },
+    #[error("Exchange error: {0}")]
+    Exchange(ExchangeError),
 }
@@ -0,0 +1,424 @@
+use std::collections::HashMap;
+
+use ethers::types::{Signature, H160, U256};
+
+use crate::{
+    exchange::{
+        actions::{
+            ApproveAgent, ApproveBuilderFee, BulkCancel, BulkCancelCloid, BulkModify, BulkOrder,
+            CDeposit, CWithdraw, ConvertToMultiSigUser, CreateSubAccount, CreateVault, MultiSig,
+            MultiSigPayload, MultiSigSigners, ScheduleCancel, SendAsset, SetReferrer, SpotSend,
+            SubAccountSpotTransfer, SubAccountUsdTransfer, TokenDelegate, TwapCancel, TwapOrder,
+            UpdateIsolatedMargin, UpdateLeverage, UsdClassTransfer, UsdSend, VaultDistribute,
+            VaultModify, VaultTransfer, Withdraw3,
+        },
+        cancel::{CancelRequest, CancelRequestCloid, ClientCancelRequestCloid},
+        modify::{ClientModifyRequest, ModifyRequest},
+        Actions, BuilderInfo, ClientCancelRequest, ClientOrderRequest, ClientTwapCancelRequest,
+        ClientTwapRequest,
+    },
+    helpers::{float_to_string_for_hashing, uuid_to_hex_string},
+    prelude::*,
+    Error,
+};
+
+/// Turns client-side requests into `Actions`.
+///
+/// Shared by `ExchangeClient` and `UnsignedTransactionBuilder` so an action signed locally
+/// and one prepared for external signing serialize and hash identically.
+pub(crate) struct ActionBuilder<'a> {
+    pub(crate) coin_to_asset: &'a HashMap<String, u32>,
+    pub(crate) is_mainnet: bool,
+}
+
+impl ActionBuilder<'_> {
+    pub(crate) fn hyperliquid_chain(&self) -> String {
+        if self.is_mainnet {
+            "Mainnet".to_string()
+        } else {
+            "Testnet".to_string()
+        }
+    }
+
+    /// Chain id of the EIP-712 domain for user-signed actions. The network is named by
+    /// `hyperliquid_chain`, so both networks sign with 421614 (0x66eee) like the official SDKs.
+    pub(crate) fn signature_chain_id(&self) -> U256 {
+        U256::from(421614)
+    }
+
+    fn asset(&self, coin: &str) -> Result<u32> {
+        self.coin_to_asset
+            .get(coin)
+            .copied()
+            .ok_or(Error::AssetNotFound)
+    }
+
+    pub(crate) fn order(
+        &self,
+        orders: Vec<ClientOrderRequest>,
+        grouping: String,
+        builder: Option<BuilderInfo>,
+    ) -> Result<Actions> {
+        let mut transformed_orders = Vec::new();
+        for order in orders {
+            transformed_orders.push(order.convert(self.coin_to_asset)?);
+        }
+
+        Ok(Actions::Order(BulkOrder {
+            orders: transformed_orders,
+            grouping,
+            builder: builder.map(|mut builder| {
+                builder.builder = builder.builder.to_lowercase();
+                builder
+            }),
+        }))
+    }
+
+    pub(crate) fn cancel(&self, cancels: Vec<ClientCancelRequest>) -> Result<Actions> {
+        let mut transformed_cancels = Vec::new();
+        for cancel in cancels {
+            transformed_cancels.push(CancelRequest {
+                asset: self.asset(&cancel.asset)?,
+                oid: cancel.oid,
+            });
+        }
+
+        Ok(Actions::Cancel(BulkCancel {
+            cancels: transformed_cancels,
+        }))
+    }
+
+    pub(crate) fn cancel_by_cloid(
+        &self,
+        cancels: Vec<ClientCancelRequestCloid>,
+    ) -> Result<Actions> {
+        let mut transformed_cancels = Vec::new();
+        for cancel in cancels {
+            transformed_cancels.push(CancelRequestCloid {
+                asset: self.asset(&cancel.asset)?,
+                cloid: uuid_to_hex_string(cancel.cloid),
+            });
+        }
+
+        Ok(Actions::CancelByCloid(BulkCancelCloid {
+            cancels: transformed_cancels,
+        }))
+    }
+
+    pub(crate) fn modify(&self, modifies: Vec<ClientModifyRequest>) -> Result<Actions> {
+        let mut transformed_modifies = Vec::new();
+        for modify in modifies {
+            transformed_modifies.push(ModifyRequest {
+                oid: modify.oid,
+                order: modify.order.convert(self.coin_to_asset)?,
+            });
+        }
+
+        Ok(Actions::BatchModify(BulkModify {
+            modifies: transformed_modifies,
+        }))
+    }
+
+    pub(crate) fn twap_order(&self, twap: ClientTwapRequest) -> Result<Actions> {
+        Ok(Actions::TwapOrder(TwapOrder {
+            twap: twap.convert(self.coin_to_asset)?,
+        }))
+    }
+
+    pub(crate) fn twap_cancel(&self, cancel: ClientTwapCancelRequest) -> Result<Actions> {
+        Ok(Actions::TwapCancel(TwapCancel {
+            asset: self.asset(&cancel.asset)?,
+            twap_id: cancel.twap_id,
+        }))
+    }
+
+    pub(crate) fn update_leverage(
+        &self,
+        leverage: u32,
+        coin: &str,
+        is_cross: bool,
+    ) -> Result<Actions> {
+        Ok(Actions::UpdateLeverage(UpdateLeverage {
+            asset: self.asset(coin)?,
+            is_cross,
+            leverage,
+        }))
+    }
+
+    pub(crate) fn update_isolated_margin(&self, amount: f64, coin: &str) -> Result<Actions> {
+        Ok(Actions::UpdateIsolatedMargin(UpdateIsolatedMargin {
+            asset: self.asset(coin)?,
+            is_buy: true,
+            ntli: (amount * 1_000_000.0).round() as i64,
+        }))
+    }
+
+    pub(crate) fn usd_send(&self, amount: &str, destination: &str, time: u64) -> Actions {
+        Actions::UsdSend(UsdSend {
+            signature_chain_id: self.signature_chain_id(),
+            hyperliquid_chain: self.hyperliquid_chain(),
+            destination: destination.to_string(),
+            amount: amount.to_string(),
+            time,
+        })
+    }
+
+    pub(crate) fn withdraw(&self, amount: &str, destination: &str, time: u64) -> Actions {
+        Actions::Withdraw3(Withdraw3 {
+            signature_chain_id: self.signature_chain_id(),
+            hyperliquid_chain: self.hyperliquid_chain(),
+            destination: destination.to_string(),
+            amount: amount.to_string(),
+            time,
+        })
+    }
+
+    pub(crate) fn spot_send(
+        &self,
+        amount: &str,
+        destination: &str,
+        token: &str,
+        time: u64,
+    ) -> Actions {
+        Actions::SpotSend(SpotSend {
+            signature_chain_id: self.signature_chain_id(),
+            hyperliquid_chain: self.hyperliquid_chain(),
+            destination: destination.to_string(),
+            token: token.to_string(),
+            amount: amount.to_string(),
+            time,
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn send_asset(
+        &self,
+        destination: &str,
+        source_dex: &str,
+        destination_dex: &str,
+        token: &str,
+        amount: &str,
+        from_sub_account: Option<H160>,
+        nonce: u64,
+    ) -> Actions {
+        Actions::SendAsset(SendAsset {
+            signature_chain_id: self.signature_chain_id(),
+            hyperliquid_chain: self.hyperliquid_chain(),
+            destination: destination.to_string(),
+            source_dex: source_dex.to_string(),
+            destination_dex: destination_dex.to_string(),
+            token: token.to_string(),
+            amount: amount.to_string(),
+            from_sub_account: from_sub_account
+                .map(|address| format!("{address:?}"))
+                .unwrap_or_default(),
+            nonce,
+        })
+    }
+
+    pub(crate) fn c_deposit(&self, wei: u64, nonce: u64) -> Actions {
+        Actions::CDeposit(CDeposit {
+            signature_chain_id: self.signature_chain_id(),
+            hyperliquid_chain: self.hyperliquid_chain(),
+            wei,
+            nonce,
+        })
+    }
+
+    pub(crate) fn c_withdraw(&self, wei: u64, nonce: u64) -> Actions {
+        Actions::CWithdraw(CWithdraw {
+            signature_chain_id: self.signature_chain_id(),
+            hyperliquid_chain: self.hyperliquid_chain(),
+            wei,
+            nonce,
+        })
+    }
+
+    pub(crate) fn token_delegate(
+        &self,
+        validator: H160,
+        wei: u64,
+        is_undelegate: bool,
+        nonce: u64,
+    ) -> Actions {
+        Actions::TokenDelegate(TokenDelegate {
+            signature_chain_id: self.signature_chain_id(),
+            hyperliquid_chain: self.hyperliquid_chain(),
+            validator,
+            wei,
+            is_undelegate,
+            nonce,
+        })
+    }
+
+    /// `vault_address` is the sub-account or vault whose balances are moved, if any
+    pub(crate) fn usd_class_transfer(
+        &self,
+        usdc: f64,
+        to_perp: bool,
+        vault_address: Option<H160>,
+        nonce: u64,
+    ) -> Actions {
+        let mut amount = float_to_string_for_hashing(usdc);
+        if let Some(vault_address) = vault_address {
+            amount = format!("{amount} subaccount:{vault_address:?}");
+        }
+        Actions::UsdClassTransfer(UsdClassTransfer {
+            signature_chain_id: self.signature_chain_id(),
+            hyperliquid_chain: self.hyperliquid_chain(),
+            amount,
+            to_perp,
+            nonce,
+        })
+    }
+
+    pub(crate) fn vault_transfer(
+        &self,
+        vault_address: H160,
+        is_deposit: bool,
+        usd: u64,
+    ) -> Actions {
+        Actions::VaultTransfer(VaultTransfer {
+            vault_address,
+            is_deposit,
+            usd,
+        })
+    }
+
+    pub(crate) fn create_vault(
+        &self,
+        name: String,
+        description: String,
+        initial_usd: u64,
+        nonce: u64,
+    ) -> Actions {
+        Actions::CreateVault(CreateVault {
+            name,
+            description,
+            initial_usd,
+            nonce,
+        })
+    }
+
+    pub(crate) fn vault_modify(
+        &self,
+        vault_address: H160,
+        allow_deposits: Option<bool>,
+        always_close_on_withdraw: Option<bool>,
+    ) -> Actions {
+        Actions::VaultModify(VaultModify {
+            vault_address,
+            allow_deposits,
+            always_close_on_withdraw,
+        })
+    }
+
+    pub(crate) fn vault_distribute(&self, vault_address: H160, usd: u64) -> Actions {
+        Actions::VaultDistribute(VaultDistribute { vault_address, usd })
+    }
+
+    pub(crate) fn create_sub_account(&self, name: String) -> Actions {
+        Actions::CreateSubAccount(CreateSubAccount { name })
+    }
+
+    pub(crate) fn sub_account_transfer(
+        &self,
+        sub_account_user: H160,
+        is_deposit: bool,
+        usd: u64,
+    ) -> Actions {
+        Actions::SubAccountTransfer(SubAccountUsdTransfer {
+            sub_account_user,
+            is_deposit,
+            usd,
+        })
+    }
+
+    pub(crate) fn sub_account_spot_transfer(
+        &self,
+        sub_account_user: H160,
+        is_deposit: bool,
+        token: &str,
+        amount: &str,
+    ) -> Actions {
+        Actions::SubAccountSpotTransfer(SubAccountSpotTransfer {
+            sub_account_user,
+            is_deposit,
+            token: token.to_string(),
+            amount: amount.to_string(),
+        })
+    }
+
+    pub(crate) fn set_referrer(&self, code: String) -> Actions {
+        Actions::SetReferrer(SetReferrer { code })
+    }
+
+    pub(crate) fn schedule_cancel(&self, time: Option<u64>) -> Actions {
+        Actions::ScheduleCancel(ScheduleCancel { time })
+    }
+
+    pub(crate) fn approve_agent(
+        &self,
+        agent_address: H160,
+        agent_name: Option<String>,
+        nonce: u64,
+    ) -> Actions {
+        Actions::ApproveAgent(ApproveAgent {
+            signature_chain_id: self.signature_chain_id(),
+            hyperliquid_chain: self.hyperliquid_chain(),
+            agent_address,
+            agent_name,
+            nonce,
+        })
+    }
+
+    pub(crate) fn approve_builder_fee(
+        &self,
+        builder: &str,
+        max_fee_rate: String,
+        nonce: u64,
+    ) -> Actions {
+        Actions::ApproveBuilderFee(ApproveBuilderFee {
+            signature_chain_id: self.signature_chain_id(),
+            hyperliquid_chain: self.hyperliquid_chain(),
+            // Ensure builder address is lowercase
+            builder: builder.to_lowercase(),
+            max_fee_rate,
+            nonce,
+        })
+    }
+
+    pub(crate) fn convert_to_multi_sig_user(
+        &self,
+        mut signers: MultiSigSigners,
+        nonce: u64,
+    ) -> Result<Actions> {
+        signers.authorized_users.sort();
+        let signers =
+            serde_json::to_string(&signers).map_err(|e| Error::JsonParse(e.to_string()))?;
+        Ok(Actions::ConvertToMultiSigUser(ConvertToMultiSigUser {
+            signature_chain_id: self.signature_chain_id(),
+            hyperliquid_chain: self.hyperliquid_chain(),
+            signers,
+            nonce,
+        }))
+    }
+
+    pub(crate) fn multi_sig(
+        &self,
+        multi_sig_user: H160,
+        outer_signer: H160,
+        inner_action: Actions,
+        signatures: Vec<Signature>,
+    ) -> Actions {
+        Actions::MultiSig(MultiSig {
+            signature_chain_id: self.signature_chain_id(),
+            signatures,
+            payload: MultiSigPayload {
+                multi_sig_user,
+                outer_signer,
+                action: Box::new(inner_action),
+            },
+        })
+    }
+}
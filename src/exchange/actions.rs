@@ -1,21 +1,43 @@
-use crate::exchange::{cancel::CancelRequest, modify::ModifyRequest, order::OrderRequest};
+use crate::exchange::{
+    cancel::CancelRequest, modify::ModifyRequest, order::OrderRequest, twap::TwapRequest,
+};
 pub(crate) use ethers::{
-    abi::{encode, ParamType, Tokenizable},
+    abi::{encode, Tokenizable},
     types::{
-        transaction::{
-            eip712,
-            eip712::{encode_eip712_type, EIP712Domain, Eip712, Eip712Error},
-        },
-        H160, U256,
+        transaction::eip712::{encode_eip712_type, EIP712Domain, Eip712, Eip712Error},
+        Signature, H160, H256, U256,
     },
     utils::keccak256,
 };
 use serde::{Deserialize, Serialize};
 
-use super::{cancel::CancelRequestCloid, BuilderInfo};
+use super::{cancel::CancelRequestCloid, Actions, BuilderInfo};
 
 pub(crate) const HYPERLIQUID_EIP_PREFIX: &str = "HyperliquidTransaction:";
 
+/// Fields of an EIP-712 struct in signing order, as (name, Solidity type)
+pub(crate) type Eip712Fields = &'static [(&'static str, &'static str)];
+
+/// EIP-712 layout of a user-signed action, shared by its `Eip712` impl and the typed data
+/// exported for external wallets
+pub(crate) trait UserSignedType {
+    /// Primary type name, without `HYPERLIQUID_EIP_PREFIX`
+    const TYPE_NAME: &'static str;
+    const FIELDS: Eip712Fields;
+}
+
+fn user_signed_type_hash<T: UserSignedType>() -> [u8; 32] {
+    let fields: Vec<String> = T::FIELDS
+        .iter()
+        .map(|(name, ty)| format!("{ty} {name}"))
+        .collect();
+    keccak256(format!(
+        "{HYPERLIQUID_EIP_PREFIX}{}({})",
+        T::TYPE_NAME,
+        fields.join(",")
+    ))
+}
+
 fn eip_712_domain(chain_id: U256) -> EIP712Domain {
     EIP712Domain {
         name: Some("HyperliquidSignTransaction".to_string()),
@@ -40,6 +62,16 @@ pub struct UsdSend {
     pub time: u64,
 }
 
+impl UserSignedType for UsdSend {
+    const TYPE_NAME: &'static str = "UsdSend";
+    const FIELDS: Eip712Fields = &[
+        ("hyperliquidChain", "string"),
+        ("destination", "string"),
+        ("amount", "string"),
+        ("time", "uint64"),
+    ];
+}
+
 impl Eip712 for UsdSend {
     type Error = Eip712Error;
 
@@ -48,15 +80,7 @@ impl Eip712 for UsdSend {
     }
 
     fn type_hash() -> Result<[u8; 32], Self::Error> {
-        Ok(eip712::make_type_hash(
-            format!("{HYPERLIQUID_EIP_PREFIX}UsdSend"),
-            &[
-                ("hyperliquidChain".to_string(), ParamType::String),
-                ("destination".to_string(), ParamType::String),
-                ("amount".to_string(), ParamType::String),
-                ("time".to_string(), ParamType::Uint(64)),
-            ],
-        ))
+        Ok(user_signed_type_hash::<Self>())
     }
 
     fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
@@ -121,6 +145,20 @@ pub struct BulkCancelCloid {
     pub cancels: Vec<CancelRequestCloid>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TwapOrder {
+    pub twap: TwapRequest,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TwapCancel {
+    #[serde(rename = "a", alias = "asset")]
+    pub asset: u32,
+    #[serde(rename = "t", alias = "twapId")]
+    pub twap_id: u64,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct ApproveAgent {
@@ -131,6 +169,16 @@ pub struct ApproveAgent {
     pub nonce: u64,
 }
 
+impl UserSignedType for ApproveAgent {
+    const TYPE_NAME: &'static str = "ApproveAgent";
+    const FIELDS: Eip712Fields = &[
+        ("hyperliquidChain", "string"),
+        ("agentAddress", "address"),
+        ("agentName", "string"),
+        ("nonce", "uint64"),
+    ];
+}
+
 impl Eip712 for ApproveAgent {
     type Error = Eip712Error;
 
@@ -139,15 +187,7 @@ impl Eip712 for ApproveAgent {
     }
 
     fn type_hash() -> Result<[u8; 32], Self::Error> {
-        Ok(eip712::make_type_hash(
-            format!("{HYPERLIQUID_EIP_PREFIX}ApproveAgent"),
-            &[
-                ("hyperliquidChain".to_string(), ParamType::String),
-                ("agentAddress".to_string(), ParamType::Address),
-                ("agentName".to_string(), ParamType::String),
-                ("nonce".to_string(), ParamType::Uint(64)),
-            ],
-        ))
+        Ok(user_signed_type_hash::<Self>())
     }
 
     fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
@@ -179,6 +219,16 @@ pub struct Withdraw3 {
     pub destination: String,
 }
 
+impl UserSignedType for Withdraw3 {
+    const TYPE_NAME: &'static str = "Withdraw";
+    const FIELDS: Eip712Fields = &[
+        ("hyperliquidChain", "string"),
+        ("destination", "string"),
+        ("amount", "string"),
+        ("time", "uint64"),
+    ];
+}
+
 impl Eip712 for Withdraw3 {
     type Error = Eip712Error;
 
@@ -187,15 +237,7 @@ impl Eip712 for Withdraw3 {
     }
 
     fn type_hash() -> Result<[u8; 32], Self::Error> {
-        Ok(eip712::make_type_hash(
-            format!("{HYPERLIQUID_EIP_PREFIX}Withdraw"),
-            &[
-                ("hyperliquidChain".to_string(), ParamType::String),
-                ("destination".to_string(), ParamType::String),
-                ("amount".to_string(), ParamType::String),
-                ("time".to_string(), ParamType::Uint(64)),
-            ],
-        ))
+        Ok(user_signed_type_hash::<Self>())
     }
 
     fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
@@ -228,6 +270,17 @@ pub struct SpotSend {
     pub time: u64,
 }
 
+impl UserSignedType for SpotSend {
+    const TYPE_NAME: &'static str = "SpotSend";
+    const FIELDS: Eip712Fields = &[
+        ("hyperliquidChain", "string"),
+        ("destination", "string"),
+        ("token", "string"),
+        ("amount", "string"),
+        ("time", "uint64"),
+    ];
+}
+
 impl Eip712 for SpotSend {
     type Error = Eip712Error;
 
@@ -236,16 +289,7 @@ impl Eip712 for SpotSend {
     }
 
     fn type_hash() -> Result<[u8; 32], Self::Error> {
-        Ok(eip712::make_type_hash(
-            format!("{HYPERLIQUID_EIP_PREFIX}SpotSend"),
-            &[
-                ("hyperliquidChain".to_string(), ParamType::String),
-                ("destination".to_string(), ParamType::String),
-                ("token".to_string(), ParamType::String),
-                ("amount".to_string(), ParamType::String),
-                ("time".to_string(), ParamType::Uint(64)),
-            ],
-        ))
+        Ok(user_signed_type_hash::<Self>())
     }
 
     fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
@@ -269,6 +313,278 @@ impl Eip712 for SpotSend {
     }
 }
 
+/// Sends `token` from the `source_dex` balance to the `destination_dex` balance of
+/// `destination`. The main perp dex is `""`, the spot balance `"spot"` and HIP-3 dexs go by
+/// their name. `from_sub_account` is the sending sub-account or vault, or `""`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SendAsset {
+    pub signature_chain_id: U256,
+    pub hyperliquid_chain: String,
+    pub destination: String,
+    pub source_dex: String,
+    pub destination_dex: String,
+    pub token: String,
+    pub amount: String,
+    pub from_sub_account: String,
+    pub nonce: u64,
+}
+
+impl UserSignedType for SendAsset {
+    const TYPE_NAME: &'static str = "SendAsset";
+    const FIELDS: Eip712Fields = &[
+        ("hyperliquidChain", "string"),
+        ("destination", "string"),
+        ("sourceDex", "string"),
+        ("destinationDex", "string"),
+        ("token", "string"),
+        ("amount", "string"),
+        ("fromSubAccount", "string"),
+        ("nonce", "uint64"),
+    ];
+}
+
+impl Eip712 for SendAsset {
+    type Error = Eip712Error;
+
+    fn domain(&self) -> Result<EIP712Domain, Self::Error> {
+        Ok(eip_712_domain(self.signature_chain_id))
+    }
+
+    fn type_hash() -> Result<[u8; 32], Self::Error> {
+        Ok(user_signed_type_hash::<Self>())
+    }
+
+    fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
+        let Self {
+            signature_chain_id: _,
+            hyperliquid_chain,
+            destination,
+            source_dex,
+            destination_dex,
+            token,
+            amount,
+            from_sub_account,
+            nonce,
+        } = self;
+        let items = vec![
+            ethers::abi::Token::Uint(Self::type_hash()?.into()),
+            encode_eip712_type(hyperliquid_chain.clone().into_token()),
+            encode_eip712_type(destination.clone().into_token()),
+            encode_eip712_type(source_dex.clone().into_token()),
+            encode_eip712_type(destination_dex.clone().into_token()),
+            encode_eip712_type(token.clone().into_token()),
+            encode_eip712_type(amount.clone().into_token()),
+            encode_eip712_type(from_sub_account.clone().into_token()),
+            encode_eip712_type(nonce.into_token()),
+        ];
+        Ok(keccak256(encode(&items)))
+    }
+}
+
+/// Moves `wei` of HYPE (8 decimals) from the spot balance into the staking balance
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CDeposit {
+    pub signature_chain_id: U256,
+    pub hyperliquid_chain: String,
+    pub wei: u64,
+    pub nonce: u64,
+}
+
+impl UserSignedType for CDeposit {
+    const TYPE_NAME: &'static str = "CDeposit";
+    const FIELDS: Eip712Fields = &[
+        ("hyperliquidChain", "string"),
+        ("wei", "uint64"),
+        ("nonce", "uint64"),
+    ];
+}
+
+impl Eip712 for CDeposit {
+    type Error = Eip712Error;
+
+    fn domain(&self) -> Result<EIP712Domain, Self::Error> {
+        Ok(eip_712_domain(self.signature_chain_id))
+    }
+
+    fn type_hash() -> Result<[u8; 32], Self::Error> {
+        Ok(user_signed_type_hash::<Self>())
+    }
+
+    fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
+        let Self {
+            signature_chain_id: _,
+            hyperliquid_chain,
+            wei,
+            nonce,
+        } = self;
+        let items = vec![
+            ethers::abi::Token::Uint(Self::type_hash()?.into()),
+            encode_eip712_type(hyperliquid_chain.clone().into_token()),
+            encode_eip712_type(wei.into_token()),
+            encode_eip712_type(nonce.into_token()),
+        ];
+        Ok(keccak256(encode(&items)))
+    }
+}
+
+/// Moves `wei` of undelegated HYPE (8 decimals) from the staking balance back to spot,
+/// after the unstaking queue
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CWithdraw {
+    pub signature_chain_id: U256,
+    pub hyperliquid_chain: String,
+    pub wei: u64,
+    pub nonce: u64,
+}
+
+impl UserSignedType for CWithdraw {
+    const TYPE_NAME: &'static str = "CWithdraw";
+    const FIELDS: Eip712Fields = &[
+        ("hyperliquidChain", "string"),
+        ("wei", "uint64"),
+        ("nonce", "uint64"),
+    ];
+}
+
+impl Eip712 for CWithdraw {
+    type Error = Eip712Error;
+
+    fn domain(&self) -> Result<EIP712Domain, Self::Error> {
+        Ok(eip_712_domain(self.signature_chain_id))
+    }
+
+    fn type_hash() -> Result<[u8; 32], Self::Error> {
+        Ok(user_signed_type_hash::<Self>())
+    }
+
+    fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
+        let Self {
+            signature_chain_id: _,
+            hyperliquid_chain,
+            wei,
+            nonce,
+        } = self;
+        let items = vec![
+            ethers::abi::Token::Uint(Self::type_hash()?.into()),
+            encode_eip712_type(hyperliquid_chain.clone().into_token()),
+            encode_eip712_type(wei.into_token()),
+            encode_eip712_type(nonce.into_token()),
+        ];
+        Ok(keccak256(encode(&items)))
+    }
+}
+
+/// Delegates `wei` of staked HYPE (8 decimals) to `validator`, or undelegates it
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenDelegate {
+    pub signature_chain_id: U256,
+    pub hyperliquid_chain: String,
+    pub validator: H160,
+    pub wei: u64,
+    pub is_undelegate: bool,
+    pub nonce: u64,
+}
+
+impl UserSignedType for TokenDelegate {
+    const TYPE_NAME: &'static str = "TokenDelegate";
+    const FIELDS: Eip712Fields = &[
+        ("hyperliquidChain", "string"),
+        ("validator", "address"),
+        ("wei", "uint64"),
+        ("isUndelegate", "bool"),
+        ("nonce", "uint64"),
+    ];
+}
+
+impl Eip712 for TokenDelegate {
+    type Error = Eip712Error;
+
+    fn domain(&self) -> Result<EIP712Domain, Self::Error> {
+        Ok(eip_712_domain(self.signature_chain_id))
+    }
+
+    fn type_hash() -> Result<[u8; 32], Self::Error> {
+        Ok(user_signed_type_hash::<Self>())
+    }
+
+    fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
+        let Self {
+            signature_chain_id: _,
+            hyperliquid_chain,
+            validator,
+            wei,
+            is_undelegate,
+            nonce,
+        } = self;
+        let items = vec![
+            ethers::abi::Token::Uint(Self::type_hash()?.into()),
+            encode_eip712_type(hyperliquid_chain.clone().into_token()),
+            encode_eip712_type(validator.into_token()),
+            encode_eip712_type(wei.into_token()),
+            encode_eip712_type(is_undelegate.into_token()),
+            encode_eip712_type(nonce.into_token()),
+        ];
+        Ok(keccak256(encode(&items)))
+    }
+}
+
+/// Moves USDC between the spot and perp balances. For a sub-account or vault the amount
+/// is suffixed with " subaccount:<address>".
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UsdClassTransfer {
+    pub signature_chain_id: U256,
+    pub hyperliquid_chain: String,
+    pub amount: String,
+    pub to_perp: bool,
+    pub nonce: u64,
+}
+
+impl UserSignedType for UsdClassTransfer {
+    const TYPE_NAME: &'static str = "UsdClassTransfer";
+    const FIELDS: Eip712Fields = &[
+        ("hyperliquidChain", "string"),
+        ("amount", "string"),
+        ("toPerp", "bool"),
+        ("nonce", "uint64"),
+    ];
+}
+
+impl Eip712 for UsdClassTransfer {
+    type Error = Eip712Error;
+
+    fn domain(&self) -> Result<EIP712Domain, Self::Error> {
+        Ok(eip_712_domain(self.signature_chain_id))
+    }
+
+    fn type_hash() -> Result<[u8; 32], Self::Error> {
+        Ok(user_signed_type_hash::<Self>())
+    }
+
+    fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
+        let Self {
+            signature_chain_id: _,
+            hyperliquid_chain,
+            amount,
+            to_perp,
+            nonce,
+        } = self;
+        let items = vec![
+            ethers::abi::Token::Uint(Self::type_hash()?.into()),
+            encode_eip712_type(hyperliquid_chain.clone().into_token()),
+            encode_eip712_type(amount.clone().into_token()),
+            encode_eip712_type(to_perp.into_token()),
+            encode_eip712_type(nonce.into_token()),
+        ];
+        Ok(keccak256(encode(&items)))
+    }
+}
+
+/// Legacy L1 form of `UsdClassTransfer`, kept for decoding
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct SpotUser {
@@ -296,6 +612,67 @@ pub struct SetReferrer {
     pub code: String,
 }
 
+/// Creates a vault led by the signer, seeded with `initial_usd` (USDC with 6 decimals)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateVault {
+    pub name: String,
+    pub description: String,
+    pub initial_usd: u64,
+    pub nonce: u64,
+}
+
+/// Changes the settings of a vault, `None` leaving a setting as it is
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultModify {
+    pub vault_address: H160,
+    pub allow_deposits: Option<bool>,
+    pub always_close_on_withdraw: Option<bool>,
+}
+
+/// Distributes `usd` (USDC with 6 decimals) of a vault's equity to its followers
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultDistribute {
+    pub vault_address: H160,
+    pub usd: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateSubAccount {
+    pub name: String,
+}
+
+/// Moves USDC between the master account's perp balance and a sub-account's
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SubAccountUsdTransfer {
+    pub sub_account_user: H160,
+    pub is_deposit: bool,
+    pub usd: u64,
+}
+
+/// Moves a spot token between the master account and a sub-account
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SubAccountSpotTransfer {
+    pub sub_account_user: H160,
+    pub is_deposit: bool,
+    pub token: String,
+    pub amount: String,
+}
+
+/// Cancels all open orders at `time`, a dead man's switch. Without a time any scheduled
+/// cancel is removed.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleCancel {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub time: Option<u64>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct ApproveBuilderFee {
@@ -306,6 +683,16 @@ pub struct ApproveBuilderFee {
     pub hyperliquid_chain: String,
 }
 
+impl UserSignedType for ApproveBuilderFee {
+    const TYPE_NAME: &'static str = "ApproveBuilderFee";
+    const FIELDS: Eip712Fields = &[
+        ("hyperliquidChain", "string"),
+        ("builder", "string"),
+        ("maxFeeRate", "string"),
+        ("nonce", "uint64"),
+    ];
+}
+
 impl Eip712 for ApproveBuilderFee {
     type Error = Eip712Error;
 
@@ -314,15 +701,7 @@ impl Eip712 for ApproveBuilderFee {
     }
 
     fn type_hash() -> Result<[u8; 32], Self::Error> {
-        Ok(eip712::make_type_hash(
-            format!("{HYPERLIQUID_EIP_PREFIX}ApproveBuilderFee"),
-            &[
-                ("hyperliquidChain".to_string(), ParamType::String),
-                ("builder".to_string(), ParamType::String),
-                ("maxFeeRate".to_string(), ParamType::String),
-                ("nonce".to_string(), ParamType::Uint(64)),
-            ],
-        ))
+        Ok(user_signed_type_hash::<Self>())
     }
 
     fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
@@ -343,3 +722,122 @@ impl Eip712 for ApproveBuilderFee {
         Ok(keccak256(encode(&items)))
     }
 }
+
+/// The users allowed to sign for a multi-sig user, and how many of them must sign
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiSigSigners {
+    pub authorized_users: Vec<H160>,
+    pub threshold: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ConvertToMultiSigUser {
+    pub signature_chain_id: U256,
+    pub hyperliquid_chain: String,
+    /// JSON encoded `MultiSigSigners`
+    pub signers: String,
+    pub nonce: u64,
+}
+
+impl UserSignedType for ConvertToMultiSigUser {
+    const TYPE_NAME: &'static str = "ConvertToMultiSigUser";
+    const FIELDS: Eip712Fields = &[
+        ("hyperliquidChain", "string"),
+        ("signers", "string"),
+        ("nonce", "uint64"),
+    ];
+}
+
+impl Eip712 for ConvertToMultiSigUser {
+    type Error = Eip712Error;
+
+    fn domain(&self) -> Result<EIP712Domain, Self::Error> {
+        Ok(eip_712_domain(self.signature_chain_id))
+    }
+
+    fn type_hash() -> Result<[u8; 32], Self::Error> {
+        Ok(user_signed_type_hash::<Self>())
+    }
+
+    fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
+        let Self {
+            signature_chain_id: _,
+            hyperliquid_chain,
+            signers,
+            nonce,
+        } = self;
+        let items = vec![
+            ethers::abi::Token::Uint(Self::type_hash()?.into()),
+            encode_eip712_type(hyperliquid_chain.clone().into_token()),
+            encode_eip712_type(signers.clone().into_token()),
+            encode_eip712_type(nonce.into_token()),
+        ];
+        Ok(keccak256(encode(&items)))
+    }
+}
+
+/// An action of a multi-sig user, carrying the signatures of its authorized users over
+/// the inner action. Submitted and signed by `outer_signer`, one of the authorized users.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiSig {
+    pub signature_chain_id: U256,
+    pub signatures: Vec<Signature>,
+    pub payload: MultiSigPayload,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiSigPayload {
+    pub multi_sig_user: H160,
+    pub outer_signer: H160,
+    pub action: Box<Actions>,
+}
+
+/// The struct the outer signer of a `MultiSig` action signs
+#[derive(Debug, Clone)]
+pub struct SendMultiSig {
+    pub signature_chain_id: U256,
+    pub hyperliquid_chain: String,
+    pub multi_sig_action_hash: H256,
+    pub nonce: u64,
+}
+
+impl UserSignedType for SendMultiSig {
+    const TYPE_NAME: &'static str = "SendMultiSig";
+    const FIELDS: Eip712Fields = &[
+        ("hyperliquidChain", "string"),
+        ("multiSigActionHash", "bytes32"),
+        ("nonce", "uint64"),
+    ];
+}
+
+impl Eip712 for SendMultiSig {
+    type Error = Eip712Error;
+
+    fn domain(&self) -> Result<EIP712Domain, Self::Error> {
+        Ok(eip_712_domain(self.signature_chain_id))
+    }
+
+    fn type_hash() -> Result<[u8; 32], Self::Error> {
+        Ok(user_signed_type_hash::<Self>())
+    }
+
+    fn struct_hash(&self) -> Result<[u8; 32], Self::Error> {
+        let Self {
+            signature_chain_id: _,
+            hyperliquid_chain,
+            multi_sig_action_hash,
+            nonce,
+        } = self;
+        let items = vec![
+            ethers::abi::Token::Uint(Self::type_hash()?.into()),
+            encode_eip712_type(hyperliquid_chain.clone().into_token()),
+            encode_eip712_type(multi_sig_action_hash.into_token()),
+            encode_eip712_type(nonce.into_token()),
+        ];
+        Ok(keccak256(encode(&items)))
+    }
+}
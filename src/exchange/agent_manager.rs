@@ -0,0 +1,258 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use ethers::{signers::LocalWallet, types::H160};
+use rand::thread_rng;
+
+use crate::{
+    helpers::generate_random_key, prelude::*, Error, ExchangeClient, ExchangeResponseStatus,
+    HyperliquidSigner, InfoClient,
+};
+
+const KEYSTORE_EXTENSION: &str = "json";
+const PENDING_EXTENSION: &str = "json.pending";
+
+/// Agent keys encrypted with a password in a local directory, one eth-keystore (Web3 Secret
+/// Storage) file per agent name
+#[derive(Debug, Clone)]
+pub struct AgentKeystore {
+    dir: PathBuf,
+}
+
+impl AgentKeystore {
+    /// Opens the keystore in `dir`, creating the directory if needed
+    pub fn new(dir: impl AsRef<Path>) -> Result<AgentKeystore> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir).map_err(|e| Error::Io(e.to_string()))?;
+        Ok(AgentKeystore { dir })
+    }
+
+    fn file_name(name: &str, extension: &str) -> Result<String> {
+        let valid = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            return Err(Error::Wallet(format!(
+                "Invalid agent name {name:?}, use letters, digits, '-' and '_'"
+            )));
+        }
+        Ok(format!("{name}.{extension}"))
+    }
+
+    fn path(&self, name: &str) -> Result<PathBuf> {
+        Ok(self.dir.join(Self::file_name(name, KEYSTORE_EXTENSION)?))
+    }
+
+    fn store_as(&self, file_name: &str, key: &[u8], password: &str) -> Result<LocalWallet> {
+        let (wallet, _) = LocalWallet::encrypt_keystore(
+            &self.dir,
+            &mut thread_rng(),
+            key,
+            password,
+            Some(file_name),
+        )
+        .map_err(|e| Error::Wallet(e.to_string()))?;
+        Ok(wallet)
+    }
+
+    /// Encrypts `key` under `name`, replacing any key already stored under it
+    pub fn store(&self, name: &str, key: &[u8], password: &str) -> Result<LocalWallet> {
+        self.store_as(&Self::file_name(name, KEYSTORE_EXTENSION)?, key, password)
+    }
+
+    /// Decrypts the agent stored under `name`
+    pub fn load(&self, name: &str, password: &str) -> Result<LocalWallet> {
+        let path = self.path(name)?;
+        if !path.exists() {
+            return Err(Error::Wallet(format!(
+                "No agent named {name:?} in the keystore"
+            )));
+        }
+        LocalWallet::decrypt_keystore(path, password).map_err(|e| Error::Wallet(e.to_string()))
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.path(name).is_ok_and(|path| path.exists())
+    }
+
+    pub fn remove(&self, name: &str) -> Result<()> {
+        fs::remove_file(self.path(name)?).map_err(|e| Error::Io(e.to_string()))
+    }
+
+    /// Names of the stored agents, sorted
+    pub fn names(&self) -> Result<Vec<String>> {
+        let mut names = Vec::new();
+        for entry in fs::read_dir(&self.dir).map_err(|e| Error::Io(e.to_string()))? {
+            let path = entry.map_err(|e| Error::Io(e.to_string()))?.path();
+            if path.extension().and_then(|extension| extension.to_str()) != Some(KEYSTORE_EXTENSION)
+            {
+                continue;
+            }
+            if let Some(name) = path.file_stem().and_then(|name| name.to_str()) {
+                names.push(name.to_string());
+            }
+        }
+        names.sort();
+        Ok(names)
+    }
+}
+
+/// An agent approved on the exchange
+#[derive(Debug, Clone)]
+pub struct ManagedAgent {
+    pub name: String,
+    pub address: H160,
+    /// Timestamp (ms) the approval expires at
+    pub valid_until: u64,
+    /// Whether the agent's key is in the local keystore
+    pub stored_locally: bool,
+}
+
+/// Creates, rotates and revokes the named agents of the client's wallet, keeping their keys
+/// in an `AgentKeystore`. Bots then load their agent with `AgentKeystore::load`.
+///
+/// Agents can sign orders and other L1 actions for the wallet but not transfers or
+/// withdrawals, so the client's wallet should be the account's own key.
+#[derive(Debug)]
+pub struct AgentManager<S: HyperliquidSigner = LocalWallet> {
+    exchange_client: Arc<ExchangeClient<S>>,
+    keystore: AgentKeystore,
+}
+
+impl<S: HyperliquidSigner> AgentManager<S> {
+    pub fn new(exchange_client: Arc<ExchangeClient<S>>, keystore: AgentKeystore) -> Self {
+        AgentManager {
+            exchange_client,
+            keystore,
+        }
+    }
+
+    pub fn keystore(&self) -> &AgentKeystore {
+        &self.keystore
+    }
+
+    /// Generates and approves a new agent under `name`, failing if the keystore already
+    /// holds one. Use `rotate` to replace it. If the exchange could not be reached the new
+    /// key is left in a `.json.pending` file named in the error.
+    pub async fn create(&self, name: &str, password: &str) -> Result<LocalWallet> {
+        if self.keystore.contains(name) {
+            return Err(Error::Wallet(format!(
+                "Agent {name:?} already exists, rotate it instead"
+            )));
+        }
+        self.approve_new_key(name, password).await
+    }
+
+    /// Replaces the agent under `name` with a newly generated one. The exchange drops the
+    /// previous agent of that name as soon as the new one is approved.
+    pub async fn rotate(&self, name: &str, password: &str) -> Result<LocalWallet> {
+        self.approve_new_key(name, password).await
+    }
+
+    /// Revokes the agent under `name` by approving the zero address in its place, then
+    /// deletes its key from the keystore
+    pub async fn revoke(&self, name: &str) -> Result<()> {
+        AgentKeystore::file_name(name, KEYSTORE_EXTENSION)?;
+        let response = self
+            .exchange_client
+            .approve_agent_address(H160::zero(), Some(name.to_string()), None)
+            .await?;
+        if let ExchangeResponseStatus::Err(e) = response {
+            return Err(Error::GenericRequest(e));
+        }
+        if self.keystore.contains(name) {
+            self.keystore.remove(name)?;
+        }
+        Ok(())
+    }
+
+    /// Agents currently approved for the client's wallet
+    pub async fn list(&self, info_client: &InfoClient) -> Result<Vec<ManagedAgent>> {
+        let user = self.exchange_client.wallet.address();
+        Ok(info_client
+            .extra_agents(user)
+            .await?
+            .into_iter()
+            .map(|agent| ManagedAgent {
+                stored_locally: self.keystore.contains(&agent.name),
+                name: agent.name,
+                address: agent.address,
+                valid_until: agent.valid_until,
+            })
+            .collect())
+    }
+
+    async fn approve_new_key(&self, name: &str, password: &str) -> Result<LocalWallet> {
+        // The key is written before it is approved so an approved agent is never lost, and
+        // only replaces the stored one once the approval went through
+        let pending = AgentKeystore::file_name(name, PENDING_EXTENSION)?;
+        let pending_path = self.keystore.dir.join(&pending);
+        let agent = self
+            .keystore
+            .store_as(&pending, &generate_random_key()?, password)?;
+
+        let response = self
+            .exchange_client
+            .approve_agent_address(
+                HyperliquidSigner::address(&agent),
+                Some(name.to_string()),
+                None,
+            )
+            .await;
+        match response {
+            Ok(ExchangeResponseStatus::Ok(_)) => {
+                fs::rename(&pending_path, self.keystore.path(name)?)
+                    .map_err(|e| Error::Io(e.to_string()))?;
+                Ok(agent)
+            }
+            Ok(ExchangeResponseStatus::Err(e)) => {
+                fs::remove_file(pending_path).map_err(|e| Error::Io(e.to_string()))?;
+                Err(Error::GenericRequest(e))
+            }
+            // The approval may still have landed, so the key stays on disk for the caller to
+            // check against `extra_agents` and then complete or discard
+            Err(e) => Err(Error::GenericRequest(format!(
+                "Approval of agent {name} is unconfirmed, its key is kept at {}: {e}",
+                pending_path.display()
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    #[test]
+    fn test_agent_keystore() {
+        let dir = std::env::temp_dir().join(format!(
+            "hl_agents_{}",
+            Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        let keystore = AgentKeystore::new(&dir).unwrap();
+        let key = generate_random_key().unwrap();
+
+        let stored = keystore.store("market-maker", &key, "password").unwrap();
+        assert!(keystore.contains("market-maker"));
+        assert_eq!(keystore.names().unwrap(), vec!["market-maker".to_string()]);
+        let loaded = keystore.load("market-maker", "password").unwrap();
+        assert_eq!(
+            HyperliquidSigner::address(&loaded),
+            HyperliquidSigner::address(&stored)
+        );
+
+        assert!(keystore.load("market-maker", "wrong").is_err());
+        assert!(keystore.load("other", "password").is_err());
+        assert!(keystore.store("../escape", &key, "password").is_err());
+
+        keystore.remove("market-maker").unwrap();
+        assert!(keystore.names().unwrap().is_empty());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
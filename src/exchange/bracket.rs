@@ -0,0 +1,253 @@
+use uuid::Uuid;
+
+use super::exchange_client::round_to_significant_and_decimal;
+use crate::{
+    meta::AssetMeta, prelude::*, ClientLimit, ClientOrder, ClientOrderRequest, ClientTrigger,
+    Error, OrderResult,
+};
+
+/// Decimals allowed in perp prices before subtracting the asset's `sz_decimals`
+const MAX_PERP_PRICE_DECIMALS: u32 = 6;
+
+/// Where a take-profit or stop-loss leg triggers
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BracketPrice {
+    /// Absolute trigger price
+    Price(f64),
+    /// Distance from the entry price in percent, e.g. `2.0` for 2%
+    Percent(f64),
+}
+
+/// An entry order with take-profit and stop-loss legs, sent as one `normalTpsl` bulk order.
+/// The legs are reduce-only market triggers on the opposite side, sized to the entry.
+///
+/// ```rust
+/// use hl_ranger::{BracketOrder, BracketPrice};
+///
+/// let bracket = BracketOrder::new("ETH", true, 0.1, 3000.0)
+///     .take_profit(BracketPrice::Percent(2.0))
+///     .stop_loss(BracketPrice::Price(2900.0));
+/// ```
+#[derive(Debug, Clone)]
+pub struct BracketOrder {
+    pub asset: String,
+    pub is_buy: bool,
+    pub sz: f64,
+    pub entry_px: f64,
+    /// Time in force of the entry, `Gtc` by default
+    pub tif: String,
+    pub cloid: Option<Uuid>,
+    pub take_profit: Option<BracketPrice>,
+    pub stop_loss: Option<BracketPrice>,
+}
+
+/// Status of each leg of a `BracketOrder`
+#[derive(Debug, Clone)]
+pub struct BracketOrderResult {
+    pub entry: OrderResult,
+    pub take_profit: Option<OrderResult>,
+    pub stop_loss: Option<OrderResult>,
+}
+
+impl BracketOrder {
+    pub fn new(asset: &str, is_buy: bool, sz: f64, entry_px: f64) -> BracketOrder {
+        BracketOrder {
+            asset: asset.to_string(),
+            is_buy,
+            sz,
+            entry_px,
+            tif: "Gtc".to_string(),
+            cloid: None,
+            take_profit: None,
+            stop_loss: None,
+        }
+    }
+
+    pub fn take_profit(mut self, take_profit: BracketPrice) -> Self {
+        self.take_profit = Some(take_profit);
+        self
+    }
+
+    pub fn stop_loss(mut self, stop_loss: BracketPrice) -> Self {
+        self.stop_loss = Some(stop_loss);
+        self
+    }
+
+    pub fn tif(mut self, tif: &str) -> Self {
+        self.tif = tif.to_string();
+        self
+    }
+
+    pub fn cloid(mut self, cloid: Uuid) -> Self {
+        self.cloid = Some(cloid);
+        self
+    }
+
+    fn trigger_px(&self, price: BracketPrice, is_take_profit: bool, decimals: u32) -> Result<f64> {
+        let leg = if is_take_profit {
+            "take profit"
+        } else {
+            "stop loss"
+        };
+        let trigger_px = match price {
+            BracketPrice::Price(px) => px,
+            BracketPrice::Percent(percent) => {
+                if !(percent > 0.0 && (is_take_profit || percent < 100.0)) {
+                    return Err(Error::GenericRequest(format!(
+                        "Invalid {leg} offset of {percent}%"
+                    )));
+                }
+                // Profit is above the entry for a long and below it for a short
+                let offset = if is_take_profit == self.is_buy {
+                    percent
+                } else {
+                    -percent
+                };
+                let px = self.entry_px * (1.0 + offset / 100.0);
+                round_to_significant_and_decimal(px, 5, decimals)
+            }
+        };
+
+        let above_entry = trigger_px > self.entry_px;
+        let below_entry = trigger_px < self.entry_px;
+        let valid = trigger_px > 0.0
+            && if is_take_profit == self.is_buy {
+                above_entry
+            } else {
+                below_entry
+            };
+        if !valid {
+            return Err(Error::GenericRequest(format!(
+                "{leg} at {trigger_px} is on the wrong side of the entry at {}",
+                self.entry_px
+            )));
+        }
+        Ok(trigger_px)
+    }
+
+    fn trigger_leg(&self, trigger_px: f64, tpsl: &str) -> ClientOrderRequest {
+        ClientOrderRequest {
+            asset: self.asset.clone(),
+            is_buy: !self.is_buy,
+            reduce_only: true,
+            limit_px: trigger_px,
+            sz: self.sz,
+            cloid: None,
+            order_type: ClientOrder::Trigger(ClientTrigger {
+                is_market: true,
+                trigger_px,
+                tpsl: tpsl.to_string(),
+            }),
+        }
+    }
+
+    /// The entry followed by its take-profit and stop-loss legs. Percentage offsets are
+    /// rounded to 5 significant figures and the asset's allowed price decimals.
+    pub fn legs<'a>(
+        &self,
+        universe: impl IntoIterator<Item = &'a AssetMeta>,
+    ) -> Result<Vec<ClientOrderRequest>> {
+        if self.take_profit.is_none() && self.stop_loss.is_none() {
+            return Err(Error::GenericRequest(
+                "A bracket order needs a take profit or a stop loss".to_string(),
+            ));
+        }
+        let sz_decimals = universe
+            .into_iter()
+            .find(|asset| asset.name == self.asset)
+            .ok_or(Error::AssetNotFound)?
+            .sz_decimals;
+        let decimals = MAX_PERP_PRICE_DECIMALS.saturating_sub(sz_decimals);
+
+        let mut legs = vec![ClientOrderRequest {
+            asset: self.asset.clone(),
+            is_buy: self.is_buy,
+            reduce_only: false,
+            limit_px: self.entry_px,
+            sz: self.sz,
+            cloid: self.cloid,
+            order_type: ClientOrder::Limit(ClientLimit {
+                tif: self.tif.clone(),
+            }),
+        }];
+        if let Some(take_profit) = self.take_profit {
+            let trigger_px = self.trigger_px(take_profit, true, decimals)?;
+            legs.push(self.trigger_leg(trigger_px, "tp"));
+        }
+        if let Some(stop_loss) = self.stop_loss {
+            let trigger_px = self.trigger_px(stop_loss, false, decimals)?;
+            legs.push(self.trigger_leg(trigger_px, "sl"));
+        }
+        Ok(legs)
+    }
+
+    /// Splits the results of the orders returned by `legs` back into the bracket's legs
+    pub fn results(&self, results: Vec<OrderResult>) -> Result<BracketOrderResult> {
+        let mut results = results.into_iter();
+        let mut next = || {
+            results.next().ok_or_else(|| {
+                Error::GenericParse("Missing status for a bracket order leg".to_string())
+            })
+        };
+        Ok(BracketOrderResult {
+            entry: next()?,
+            take_profit: self.take_profit.map(|_| next()).transpose()?,
+            stop_loss: self.stop_loss.map(|_| next()).transpose()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn universe() -> Vec<AssetMeta> {
+        vec![AssetMeta {
+            name: "ETH".to_string(),
+            sz_decimals: 4,
+            max_leverage: 25,
+            margin_table_id: 25,
+            only_isolated: None,
+            is_delisted: None,
+        }]
+    }
+
+    fn trigger(leg: &ClientOrderRequest) -> &ClientTrigger {
+        match &leg.order_type {
+            ClientOrder::Trigger(trigger) => trigger,
+            ClientOrder::Limit(_) => panic!("expected a trigger leg"),
+        }
+    }
+
+    #[test]
+    fn test_bracket_legs() {
+        let long = BracketOrder::new("ETH", true, 0.1, 3000.0)
+            .take_profit(BracketPrice::Percent(2.5))
+            .stop_loss(BracketPrice::Price(2900.0));
+        let legs = long.legs(&universe()).unwrap();
+        assert_eq!(legs.len(), 3);
+        assert!(legs[0].is_buy && !legs[0].reduce_only);
+        assert!(legs[1..].iter().all(|leg| !leg.is_buy && leg.reduce_only));
+        assert_eq!(trigger(&legs[1]).trigger_px, 3075.0);
+        assert_eq!(trigger(&legs[1]).tpsl, "tp");
+        assert_eq!(trigger(&legs[2]).trigger_px, 2900.0);
+        assert_eq!(trigger(&legs[2]).tpsl, "sl");
+
+        let short = BracketOrder::new("ETH", false, 0.1, 3000.0)
+            .stop_loss(BracketPrice::Percent(1.0 / 3.0));
+        let legs = short.legs(&universe()).unwrap();
+        assert_eq!(legs.len(), 2);
+        assert!(legs[1].is_buy);
+        // Rounded to 5 significant figures
+        assert_eq!(trigger(&legs[1]).trigger_px, 3010.0);
+
+        let wrong_side =
+            BracketOrder::new("ETH", true, 0.1, 3000.0).take_profit(BracketPrice::Price(2950.0));
+        assert!(wrong_side.legs(&universe()).is_err());
+        let no_legs = BracketOrder::new("ETH", true, 0.1, 3000.0);
+        assert!(no_legs.legs(&universe()).is_err());
+        let unknown =
+            BracketOrder::new("BTC", true, 0.1, 3000.0).stop_loss(BracketPrice::Percent(1.0));
+        assert!(unknown.legs(&universe()).is_err());
+    }
+}
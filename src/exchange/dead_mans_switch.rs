@@ -0,0 +1,169 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use ethers::signers::LocalWallet;
+use log::{error, warn};
+use tokio::{spawn, task::JoinHandle, time};
+
+use crate::{prelude::*, Error, ExchangeClient, ExchangeResponseStatus, HyperliquidSigner};
+
+/// The exchange rejects scheduled cancels less than 5 seconds away
+pub const MIN_SCHEDULE_CANCEL_DELAY: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy)]
+pub struct ScheduleCancelConfig {
+    /// How far ahead open orders are scheduled to be cancelled
+    pub window: Duration,
+    /// How often the scheduled cancel is pushed back, shorter than `window`
+    pub rearm_interval: Duration,
+    /// Stop pushing the cancel back once `DeadMansSwitch::heartbeat` has not been called for
+    /// this long, so a stalled event loop lets it fire as well as a crash
+    pub heartbeat_timeout: Option<Duration>,
+}
+
+impl Default for ScheduleCancelConfig {
+    fn default() -> Self {
+        ScheduleCancelConfig {
+            window: Duration::from_secs(60),
+            rearm_interval: Duration::from_secs(15),
+            heartbeat_timeout: None,
+        }
+    }
+}
+
+impl ScheduleCancelConfig {
+    /// Checks the config is one `DeadMansSwitch::start` accepts
+    pub fn validate(&self) -> Result<()> {
+        if self.window < MIN_SCHEDULE_CANCEL_DELAY {
+            return Err(Error::GenericRequest(format!(
+                "schedule cancel window {:?} is shorter than {MIN_SCHEDULE_CANCEL_DELAY:?}",
+                self.window
+            )));
+        }
+        if self.rearm_interval.is_zero() || self.rearm_interval >= self.window {
+            return Err(Error::GenericRequest(format!(
+                "schedule cancel rearm interval {:?} must be between zero and the window {:?}",
+                self.rearm_interval, self.window
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Keeps a `scheduleCancel` armed in the background, pushing it `window` into the future
+/// every `rearm_interval`. If the process dies or stops sending heartbeats, the exchange
+/// cancels all open orders once the last schedule runs out.
+///
+/// Dropping the switch stops re-arming but leaves the last schedule in place. Use `disarm`
+/// to remove it as well.
+#[derive(Debug)]
+pub struct DeadMansSwitch<S: HyperliquidSigner + 'static = LocalWallet> {
+    exchange_client: Arc<ExchangeClient<S>>,
+    last_heartbeat: Arc<Mutex<Instant>>,
+    stop_flag: Arc<AtomicBool>,
+    task: JoinHandle<()>,
+}
+
+impl<S: HyperliquidSigner + 'static> DeadMansSwitch<S> {
+    pub fn start(
+        exchange_client: Arc<ExchangeClient<S>>,
+        config: ScheduleCancelConfig,
+    ) -> Result<DeadMansSwitch<S>> {
+        config.validate()?;
+        let last_heartbeat = Arc::new(Mutex::new(Instant::now()));
+        let stop_flag = Arc::new(AtomicBool::new(false));
+
+        let task = {
+            let exchange_client = Arc::clone(&exchange_client);
+            let last_heartbeat = Arc::clone(&last_heartbeat);
+            let stop_flag = Arc::clone(&stop_flag);
+            spawn(async move {
+                while !stop_flag.load(Ordering::Relaxed) {
+                    let stalled = match (config.heartbeat_timeout, last_heartbeat.lock()) {
+                        (Some(timeout), Ok(last_heartbeat)) => last_heartbeat.elapsed() > timeout,
+                        _ => false,
+                    };
+                    if stalled {
+                        warn!(
+                            "No heartbeat within {:?}, not re-arming the scheduled cancel",
+                            config.heartbeat_timeout
+                        );
+                    } else {
+                        let time = exchange_client.nonce_manager.now_ms()
+                            + config.window.as_millis() as u64;
+                        match exchange_client.schedule_cancel(Some(time), None).await {
+                            Ok(ExchangeResponseStatus::Ok(_)) => {}
+                            Ok(ExchangeResponseStatus::Err(e)) => {
+                                error!("Error re-arming scheduled cancel: {e}")
+                            }
+                            Err(e) => error!("Error re-arming scheduled cancel: {e}"),
+                        }
+                    }
+                    time::sleep(config.rearm_interval).await;
+                }
+                warn!("schedule cancel task stopped");
+            })
+        };
+
+        Ok(DeadMansSwitch {
+            exchange_client,
+            last_heartbeat,
+            stop_flag,
+            task,
+        })
+    }
+
+    /// Signals the process is still healthy, see `ScheduleCancelConfig::heartbeat_timeout`
+    pub fn heartbeat(&self) {
+        if let Ok(mut last_heartbeat) = self.last_heartbeat.lock() {
+            *last_heartbeat = Instant::now();
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        !self.task.is_finished()
+    }
+
+    /// Stops re-arming and removes the scheduled cancel
+    pub async fn disarm(self) -> Result<ExchangeResponseStatus> {
+        self.stop_flag.store(true, Ordering::Relaxed);
+        self.task.abort();
+        self.exchange_client.schedule_cancel(None, None).await
+    }
+}
+
+impl<S: HyperliquidSigner + 'static> Drop for DeadMansSwitch<S> {
+    fn drop(&mut self) {
+        self.stop_flag.store(true, Ordering::Relaxed);
+        self.task.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_schedule_cancel_config() {
+        assert!(ScheduleCancelConfig::default().validate().is_ok());
+
+        let too_short = ScheduleCancelConfig {
+            window: Duration::from_secs(4),
+            rearm_interval: Duration::from_secs(1),
+            heartbeat_timeout: None,
+        };
+        assert!(too_short.validate().is_err());
+
+        let rearm_too_late = ScheduleCancelConfig {
+            window: Duration::from_secs(30),
+            rearm_interval: Duration::from_secs(30),
+            heartbeat_timeout: None,
+        };
+        assert!(rearm_too_late.validate().is_err());
+    }
+}
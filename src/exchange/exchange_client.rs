@@ -1,53 +1,148 @@
-use crate::signature::sign_typed_data;
 use crate::{
     exchange::{
         actions::{
-            ApproveAgent, ApproveBuilderFee, BulkCancel, BulkModify, BulkOrder, SetReferrer,
-            UpdateIsolatedMargin, UpdateLeverage, UsdSend,
+            ApproveAgent, ApproveBuilderFee, BulkCancel, BulkModify, BulkOrder, Eip712Fields,
+            SetReferrer, UpdateIsolatedMargin, UpdateLeverage, UsdSend, UserSignedType,
         },
-        cancel::{CancelRequest, CancelRequestCloid},
-        modify::{ClientModifyRequest, ModifyRequest},
-        ClientCancelRequest, ClientOrderRequest,
+        modify::ClientModifyRequest,
+        ActionBuilder, ClientCancelRequest, ClientOrderRequest, ClientTwapCancelRequest,
+        ClientTwapRequest,
     },
-    helpers::{generate_random_key, next_nonce, uuid_to_hex_string},
+    helpers::generate_random_key,
     info::info_client::InfoClient,
-    meta::Meta,
+    meta::{AssetMeta, Meta, MetaSnapshot, SpotMeta},
+    nonce::{check_nonce_window, default_nonce_manager, NonceManager},
     prelude::*,
     req::HttpClient,
-    signature::sign_l1_action,
-    BaseUrl, BulkCancelCloid, Error, ExchangeResponseStatus,
+    signature::{agent::l1, recover_signer, sign_l1_action, sign_typed_data, HyperliquidSigner},
+    unsigned::bridge,
+    BaseUrl, BulkCancelCloid, CancelAllReport, CancelByCloidResult, CancelResult, DefaultResponse,
+    Error, ExchangeError, ExchangeResponseStatus, FlattenReport, OpenOrdersResponse, OrderResult,
+    PerpMeta, UnsignedTransactionComponents,
+};
+use crate::{
+    unsigned::user_signed_typed_data, CDeposit, CWithdraw, ConvertToMultiSigUser, CreateSubAccount,
+    CreateVault, MultiSig, MultiSigSigners, ScheduleCancel, SendAsset, SendMultiSig, SpotSend,
+    SpotUser, SubAccountSpotTransfer, SubAccountUsdTransfer, TokenDelegate, TwapCancel, TwapOrder,
+    TypedExchangeResponse, UsdClassTransfer, VaultDistribute, VaultModify, VaultTransfer,
+    Withdraw3,
 };
-use crate::{ClassTransfer, SpotSend, SpotUser, VaultTransfer, Withdraw3};
 use ethers::{
     abi::AbiEncode,
-    signers::{LocalWallet, Signer},
-    types::{Signature, H160, H256},
+    signers::LocalWallet,
+    types::{
+        transaction::eip712::{Eip712, TypedData},
+        Signature, TransactionRequest, H160, H256, U256,
+    },
 };
 use log::debug;
 use reqwest::Client;
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, PoisonError, RwLock, RwLockReadGuard},
+};
 
 use super::cancel::ClientCancelRequestCloid;
+use super::kill_switch::{
+    batch_error, close_order, floor_to_decimals, is_spot_asset, KILL_SWITCH_BATCH_SIZE,
+};
 use super::order::{MarketCloseParams, MarketOrderParams};
-use super::{BuilderInfo, ClientLimit, ClientOrder};
+use super::{
+    AssetFilter, BracketOrder, BracketOrderResult, BuilderInfo, ClientLimit, ClientOrder,
+    ClientTrigger, Grouping,
+};
 
 #[derive(Debug)]
-pub struct ExchangeClient {
+pub struct ExchangeClient<S: HyperliquidSigner = LocalWallet> {
     pub http_client: HttpClient,
-    pub wallet: LocalWallet,
-    pub meta: Meta,
+    pub wallet: S,
+    /// Queries the info endpoint of the same API, reused by market orders and the helpers
+    /// built on them
+    pub info_client: InfoClient,
     pub vault_address: Option<H160>,
+    meta_cache: RwLock<MetaCache>,
+    pub nonce_manager: Arc<dyn NonceManager>,
+    /// Timestamp (ms) after which the exchange rejects actions signed by this client
+    pub expires_after: Option<u64>,
+}
+
+/// The meta an `ExchangeClient` maps coins with, replaced as a whole by `refresh_meta`
+#[derive(Debug, Clone)]
+pub struct MetaCache {
+    /// Meta of the default perp dex
+    pub meta: Meta,
+    /// Meta of every perp dex the client maps, including the default one
+    pub perp_meta: Vec<PerpMeta>,
+    pub spot_meta: SpotMeta,
     pub coin_to_asset: HashMap<String, u32>,
 }
 
+impl MetaCache {
+    /// The snapshot must contain the meta of the default perp dex
+    fn from_snapshot(snapshot: MetaSnapshot) -> Result<MetaCache> {
+        let meta = snapshot.default_perp_meta().cloned().ok_or_else(|| {
+            Error::GenericParse("Snapshot has no default perp dex meta".to_string())
+        })?;
+        Ok(MetaCache {
+            meta,
+            coin_to_asset: snapshot.coin_to_asset(),
+            perp_meta: snapshot.perp_meta,
+            spot_meta: snapshot.spot_meta,
+        })
+    }
+
+    /// Assets of every perp dex
+    pub fn perp_universe(&self) -> impl Iterator<Item = &AssetMeta> {
+        self.perp_meta.iter().flat_map(|perp| &perp.meta.universe)
+    }
+
+    /// Name of the HIP-3 dex the perp `coin` is listed on, `None` for the default dex
+    pub fn perp_dex(&self, coin: &str) -> Option<String> {
+        self.perp_meta
+            .iter()
+            .find(|perp| perp.meta.universe.iter().any(|asset| asset.name == coin))
+            .and_then(|perp| perp.dex.as_ref())
+            .map(|dex| dex.name.clone())
+    }
+
+    /// Every perp dex the client maps, `None` being the default dex
+    pub fn perp_dexs(&self) -> Vec<Option<String>> {
+        let mut dexs = vec![None];
+        dexs.extend(
+            self.perp_meta
+                .iter()
+                .filter_map(|perp| perp.dex.as_ref().map(|dex| Some(dex.name.clone()))),
+        );
+        dexs
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
-struct ExchangePayload {
-    action: serde_json::Value,
-    signature: Signature,
-    nonce: u64,
-    vault_address: Option<H160>,
+pub(crate) struct ExchangePayload {
+    pub(crate) action: serde_json::Value,
+    pub(crate) signature: Signature,
+    pub(crate) nonce: u64,
+    pub(crate) vault_address: Option<H160>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) expires_after: Option<u64>,
+}
+
+impl ExchangePayload {
+    pub(crate) async fn post<T: DeserializeOwned>(
+        &self,
+        http_client: &HttpClient,
+    ) -> Result<ExchangeResponseStatus<T>> {
+        let res = serde_json::to_string(self).map_err(|e| Error::JsonParse(e.to_string()))?;
+        debug!("Sending request {res:?}");
+
+        let output = &http_client
+            .post("/exchange", res)
+            .await
+            .map_err(|e| Error::JsonParse(e.to_string()))?;
+        serde_json::from_str(output).map_err(|e| Error::JsonParse(e.to_string()))
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -61,143 +156,466 @@ pub enum Actions {
     Cancel(BulkCancel),
     CancelByCloid(BulkCancelCloid),
     BatchModify(BulkModify),
+    TwapOrder(TwapOrder),
+    TwapCancel(TwapCancel),
     ApproveAgent(ApproveAgent),
     Withdraw3(Withdraw3),
     SpotUser(SpotUser),
+    UsdClassTransfer(UsdClassTransfer),
+    SendAsset(SendAsset),
     VaultTransfer(VaultTransfer),
+    CreateVault(CreateVault),
+    VaultModify(VaultModify),
+    VaultDistribute(VaultDistribute),
     SpotSend(SpotSend),
+    CDeposit(CDeposit),
+    CWithdraw(CWithdraw),
+    TokenDelegate(TokenDelegate),
     SetReferrer(SetReferrer),
+    ScheduleCancel(ScheduleCancel),
+    CreateSubAccount(CreateSubAccount),
+    SubAccountTransfer(SubAccountUsdTransfer),
+    SubAccountSpotTransfer(SubAccountSpotTransfer),
     ApproveBuilderFee(ApproveBuilderFee),
+    ConvertToMultiSigUser(ConvertToMultiSigUser),
+    MultiSig(MultiSig),
+}
+
+/// Keccak hash of the msgpack encoded action followed by the nonce, vault address and
+/// expiry, the connection id signed through `l1::Agent`
+pub(crate) fn hash_action<T: Serialize>(
+    action: &T,
+    timestamp: u64,
+    vault_address: Option<H160>,
+    expires_after: Option<u64>,
+) -> Result<H256> {
+    let mut bytes = rmp_serde::to_vec_named(action).map_err(|e| Error::RmpParse(e.to_string()))?;
+    bytes.extend(timestamp.to_be_bytes());
+    if let Some(vault_address) = vault_address {
+        bytes.push(1);
+        bytes.extend(vault_address.to_fixed_bytes());
+    } else {
+        bytes.push(0);
+    }
+    if let Some(expires_after) = expires_after {
+        bytes.push(0);
+        bytes.extend(expires_after.to_be_bytes());
+    }
+    Ok(H256(ethers::utils::keccak256(bytes)))
+}
+
+impl MultiSig {
+    /// The struct `outer_signer` signs to submit this action
+    pub fn envelope(
+        &self,
+        nonce: u64,
+        vault_address: Option<H160>,
+        expires_after: Option<u64>,
+        is_mainnet: bool,
+    ) -> Result<SendMultiSig> {
+        Ok(SendMultiSig {
+            signature_chain_id: self.signature_chain_id,
+            hyperliquid_chain: if is_mainnet { "Mainnet" } else { "Testnet" }.to_string(),
+            // The action is hashed without its "type" tag
+            multi_sig_action_hash: hash_action(self, nonce, vault_address, expires_after)?,
+            nonce,
+        })
+    }
 }
 
 impl Actions {
-    pub fn hash(&self, timestamp: u64, vault_address: Option<H160>) -> Result<H256> {
-        let mut bytes =
-            rmp_serde::to_vec_named(self).map_err(|e| Error::RmpParse(e.to_string()))?;
-        bytes.extend(timestamp.to_be_bytes());
-        if let Some(vault_address) = vault_address {
-            bytes.push(1);
-            bytes.extend(vault_address.to_fixed_bytes());
+    pub fn hash(
+        &self,
+        timestamp: u64,
+        vault_address: Option<H160>,
+        expires_after: Option<u64>,
+    ) -> Result<H256> {
+        hash_action(self, timestamp, vault_address, expires_after)
+    }
+
+    /// The vault address posted along with this action. Class transfers and `sendAsset` name
+    /// the sub-account or vault in the action instead.
+    pub(crate) fn payload_vault_address(&self, vault_address: Option<H160>) -> Option<H160> {
+        match self {
+            Actions::UsdClassTransfer(_) | Actions::SendAsset(_) => None,
+            _ => vault_address,
+        }
+    }
+
+    /// The expiry posted along with this action. Only actions signed through `l1::Agent` and
+    /// multi-sig actions cover it in their signature.
+    pub(crate) fn payload_expires_after(&self, expires_after: Option<u64>) -> Option<u64> {
+        if self.is_user_signed() && !matches!(self, Actions::MultiSig(_)) {
+            None
         } else {
-            bytes.push(0);
+            expires_after
+        }
+    }
+
+    /// Whether the action is signed directly as an EIP-712 struct by the user, rather than
+    /// through the `l1::Agent` wrapper that agents are also allowed to sign
+    pub fn is_user_signed(&self) -> bool {
+        matches!(
+            self,
+            Actions::UsdSend(_)
+                | Actions::Withdraw3(_)
+                | Actions::SpotSend(_)
+                | Actions::UsdClassTransfer(_)
+                | Actions::SendAsset(_)
+                | Actions::CDeposit(_)
+                | Actions::CWithdraw(_)
+                | Actions::TokenDelegate(_)
+                | Actions::ApproveAgent(_)
+                | Actions::ApproveBuilderFee(_)
+                | Actions::ConvertToMultiSigUser(_)
+                | Actions::MultiSig(_)
+        )
+    }
+
+    /// Primary type and fields of the EIP-712 struct a user-signed action is signed as.
+    /// `None` for L1 actions and for `MultiSig`, whose outer signer signs a `SendMultiSig`.
+    pub(crate) fn user_signed_eip712_type(&self) -> Option<(&'static str, Eip712Fields)> {
+        fn layout<T: UserSignedType>() -> Option<(&'static str, Eip712Fields)> {
+            Some((T::TYPE_NAME, T::FIELDS))
+        }
+        match self {
+            Actions::UsdSend(_) => layout::<UsdSend>(),
+            Actions::Withdraw3(_) => layout::<Withdraw3>(),
+            Actions::SpotSend(_) => layout::<SpotSend>(),
+            Actions::UsdClassTransfer(_) => layout::<UsdClassTransfer>(),
+            Actions::SendAsset(_) => layout::<SendAsset>(),
+            Actions::CDeposit(_) => layout::<CDeposit>(),
+            Actions::CWithdraw(_) => layout::<CWithdraw>(),
+            Actions::TokenDelegate(_) => layout::<TokenDelegate>(),
+            Actions::ApproveAgent(_) => layout::<ApproveAgent>(),
+            Actions::ApproveBuilderFee(_) => layout::<ApproveBuilderFee>(),
+            Actions::ConvertToMultiSigUser(_) => layout::<ConvertToMultiSigUser>(),
+            Actions::MultiSig(_)
+            | Actions::UpdateLeverage(_)
+            | Actions::UpdateIsolatedMargin(_)
+            | Actions::Order(_)
+            | Actions::Cancel(_)
+            | Actions::CancelByCloid(_)
+            | Actions::BatchModify(_)
+            | Actions::TwapOrder(_)
+            | Actions::TwapCancel(_)
+            | Actions::SpotUser(_)
+            | Actions::VaultTransfer(_)
+            | Actions::CreateVault(_)
+            | Actions::VaultModify(_)
+            | Actions::VaultDistribute(_)
+            | Actions::SetReferrer(_)
+            | Actions::ScheduleCancel(_)
+            | Actions::CreateSubAccount(_)
+            | Actions::SubAccountTransfer(_)
+            | Actions::SubAccountSpotTransfer(_) => None,
+        }
+    }
+
+    /// The EIP-712 digest a signature over this action must cover
+    pub fn signing_digest(
+        &self,
+        nonce: u64,
+        vault_address: Option<H160>,
+        expires_after: Option<u64>,
+        is_mainnet: bool,
+    ) -> Result<H256> {
+        let encoded = match self {
+            Actions::UsdSend(usd_send) => usd_send.encode_eip712(),
+            Actions::Withdraw3(withdraw) => withdraw.encode_eip712(),
+            Actions::SpotSend(spot_send) => spot_send.encode_eip712(),
+            Actions::UsdClassTransfer(transfer) => transfer.encode_eip712(),
+            Actions::SendAsset(send_asset) => send_asset.encode_eip712(),
+            Actions::CDeposit(deposit) => deposit.encode_eip712(),
+            Actions::CWithdraw(withdraw) => withdraw.encode_eip712(),
+            Actions::TokenDelegate(delegate) => delegate.encode_eip712(),
+            Actions::ApproveAgent(approve_agent) => approve_agent.encode_eip712(),
+            Actions::ApproveBuilderFee(approve_builder_fee) => approve_builder_fee.encode_eip712(),
+            Actions::ConvertToMultiSigUser(convert) => convert.encode_eip712(),
+            Actions::MultiSig(multi_sig) => multi_sig
+                .envelope(nonce, vault_address, expires_after, is_mainnet)?
+                .encode_eip712(),
+            _ => l1::Agent {
+                source: if is_mainnet { "a" } else { "b" }.to_string(),
+                connection_id: self.hash(nonce, vault_address, expires_after)?,
+            }
+            .encode_eip712(),
+        }
+        .map_err(|e| Error::Eip712(e.to_string()))?;
+        Ok(H256::from(encoded))
+    }
+
+    /// The digest each authorized user of `multi_sig_user` signs to approve this action
+    /// as the inner action of a `MultiSig` submitted by `outer_signer`
+    pub fn multi_sig_signing_digest(
+        &self,
+        multi_sig_user: H160,
+        outer_signer: H160,
+        nonce: u64,
+        vault_address: Option<H160>,
+        expires_after: Option<u64>,
+        is_mainnet: bool,
+    ) -> Result<H256> {
+        if self.is_user_signed() {
+            // The action's own EIP-712 struct, extended with the multi-sig user and outer signer
+            let action = serde_json::to_value(self).map_err(|e| Error::JsonParse(e.to_string()))?;
+            let chain_id = action
+                .get("signatureChainId")
+                .cloned()
+                .and_then(|chain_id| serde_json::from_value(chain_id).ok())
+                .ok_or_else(|| Error::Eip712("Action has no signatureChainId".to_string()))?;
+            let typed_data =
+                user_signed_typed_data(&action, chain_id, Some((multi_sig_user, outer_signer)))?;
+            let typed_data: TypedData =
+                serde_json::from_value(typed_data).map_err(|e| Error::JsonParse(e.to_string()))?;
+            let encoded = typed_data
+                .encode_eip712()
+                .map_err(|e| Error::Eip712(e.to_string()))?;
+            return Ok(H256::from(encoded));
         }
-        Ok(H256(ethers::utils::keccak256(bytes)))
+
+        let envelope = (multi_sig_user, outer_signer, self);
+        let encoded = l1::Agent {
+            source: if is_mainnet { "a" } else { "b" }.to_string(),
+            connection_id: hash_action(&envelope, nonce, vault_address, expires_after)?,
+        }
+        .encode_eip712()
+        .map_err(|e| Error::Eip712(e.to_string()))?;
+        Ok(H256::from(encoded))
+    }
+
+    /// Recovers the address that signed this action
+    pub fn recover_signer(
+        &self,
+        nonce: u64,
+        vault_address: Option<H160>,
+        expires_after: Option<u64>,
+        is_mainnet: bool,
+        signature: &Signature,
+    ) -> Result<H160> {
+        recover_signer(
+            self.signing_digest(nonce, vault_address, expires_after, is_mainnet)?,
+            signature,
+        )
     }
 }
 
-impl ExchangeClient {
+impl<S: HyperliquidSigner> ExchangeClient<S> {
+    /// Maps the assets of every perp dex, including HIP-3 dexs. Passing `meta` skips fetching
+    /// it but only maps the default dex.
     pub async fn new(
         client: Option<Client>,
-        wallet: LocalWallet,
+        wallet: S,
         base_url: Option<BaseUrl>,
         meta: Option<Meta>,
         vault_address: Option<H160>,
-    ) -> Result<ExchangeClient> {
-        let client = client.unwrap_or_default();
+    ) -> Result<ExchangeClient<S>> {
         let base_url = base_url.unwrap_or(BaseUrl::Mainnet);
 
-        let info = InfoClient::new(None, Some(base_url)).await?;
-        let meta = if let Some(meta) = meta {
-            meta
+        let info = InfoClient::new(client.clone(), Some(base_url)).await?;
+        let perp_meta = if let Some(meta) = meta {
+            vec![PerpMeta { dex: None, meta }]
         } else {
-            info.meta().await?
+            info.global_meta().await?
+        };
+        let snapshot = MetaSnapshot {
+            perp_meta,
+            spot_meta: info.spot_meta().await?,
         };
 
-        let mut coin_to_asset = HashMap::new();
-        for (asset_ind, asset) in meta.universe.iter().enumerate() {
-            coin_to_asset.insert(asset.name.clone(), asset_ind as u32);
-        }
+        Self::from_snapshot(client, wallet, Some(base_url), snapshot, vault_address)
+    }
 
-        coin_to_asset = info
-            .spot_meta()
-            .await?
-            .add_pair_and_name_to_index_map(coin_to_asset);
+    /// Builds the client from a previously saved `MetaSnapshot` without any network access.
+    /// The snapshot must contain the meta of the default perp dex.
+    pub fn from_snapshot(
+        client: Option<Client>,
+        wallet: S,
+        base_url: Option<BaseUrl>,
+        snapshot: MetaSnapshot,
+        vault_address: Option<H160>,
+    ) -> Result<ExchangeClient<S>> {
+        let client = client.unwrap_or_default();
+        let base_url = base_url.unwrap_or(BaseUrl::Mainnet);
+
+        let meta_cache = MetaCache::from_snapshot(snapshot)?;
+        let http_client = HttpClient {
+            client,
+            base_url: base_url.get_url(),
+        };
 
         Ok(ExchangeClient {
             wallet,
-            meta,
+            info_client: InfoClient::from_http_client(&http_client),
             vault_address,
-            http_client: HttpClient {
-                client,
-                base_url: base_url.get_url(),
-            },
-            coin_to_asset,
+            http_client,
+            meta_cache: RwLock::new(meta_cache),
+            nonce_manager: default_nonce_manager(),
+            expires_after: None,
+        })
+    }
+
+    /// Refetches the meta of every perp dex and of spot, e.g. to trade newly listed assets.
+    /// Works through a shared reference, so clients held in an `Arc` (by `DeadMansSwitch`,
+    /// `MarketMaker` or `AgentManager`) can be refreshed while in use.
+    pub async fn refresh_meta(&self) -> Result<()> {
+        let snapshot = MetaSnapshot::fetch(&self.info_client).await?;
+        let meta_cache = MetaCache::from_snapshot(snapshot)?;
+        *self
+            .meta_cache
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = meta_cache;
+        Ok(())
+    }
+
+    /// The cached meta. `refresh_meta` waits for the guard to be dropped, so do not hold it
+    /// across an `.await`.
+    pub fn meta(&self) -> RwLockReadGuard<'_, MetaCache> {
+        // The cache is only ever replaced whole, so a poisoned lock still holds valid meta
+        self.meta_cache
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Replaces the process-wide default nonce manager, e.g. to inject a clock or persist nonces
+    pub fn with_nonce_manager(mut self, nonce_manager: Arc<dyn NonceManager>) -> Self {
+        self.nonce_manager = nonce_manager;
+        self
+    }
+
+    /// Has the exchange reject actions signed by this client after `expires_after` (ms), so
+    /// a delayed request is not executed late. User-signed actions such as transfers do not
+    /// support an expiry and are sent without it.
+    pub fn with_expires_after(mut self, expires_after: Option<u64>) -> Self {
+        self.expires_after = expires_after;
+        self
+    }
+
+    fn next_nonce(&self, wallet: Option<&S>) -> Result<u64> {
+        self.nonce_manager
+            .next_nonce(wallet.unwrap_or(&self.wallet).address())
+    }
+
+    /// Builds an action with the cached asset mapping, released before `f` returns
+    fn with_actions<T>(&self, f: impl FnOnce(&ActionBuilder<'_>) -> T) -> T {
+        let meta = self.meta();
+        f(&ActionBuilder {
+            coin_to_asset: &meta.coin_to_asset,
+            is_mainnet: self.http_client.is_mainnet(),
         })
     }
 
-    async fn post(
+    /// Signs `action` with the given wallet, or the client's own, into a payload ready
+    /// to be posted to `/exchange`
+    pub(crate) async fn sign_action(
         &self,
-        action: serde_json::Value,
-        signature: Signature,
+        action: Actions,
         nonce: u64,
-    ) -> Result<ExchangeResponseStatus> {
-        let exchange_payload = ExchangePayload {
+        wallet: Option<&S>,
+    ) -> Result<ExchangePayload> {
+        let wallet = wallet.unwrap_or(&self.wallet);
+        let signature = match &action {
+            Actions::UsdSend(usd_send) => sign_typed_data(usd_send, wallet).await?,
+            Actions::Withdraw3(withdraw) => sign_typed_data(withdraw, wallet).await?,
+            Actions::SpotSend(spot_send) => sign_typed_data(spot_send, wallet).await?,
+            Actions::UsdClassTransfer(transfer) => sign_typed_data(transfer, wallet).await?,
+            Actions::SendAsset(send_asset) => sign_typed_data(send_asset, wallet).await?,
+            Actions::CDeposit(deposit) => sign_typed_data(deposit, wallet).await?,
+            Actions::CWithdraw(withdraw) => sign_typed_data(withdraw, wallet).await?,
+            Actions::TokenDelegate(delegate) => sign_typed_data(delegate, wallet).await?,
+            Actions::ApproveAgent(approve_agent) => sign_typed_data(approve_agent, wallet).await?,
+            Actions::ApproveBuilderFee(approve_builder_fee) => {
+                sign_typed_data(approve_builder_fee, wallet).await?
+            }
+            Actions::ConvertToMultiSigUser(convert) => sign_typed_data(convert, wallet).await?,
+            Actions::MultiSig(multi_sig) => {
+                let envelope = multi_sig.envelope(
+                    nonce,
+                    self.vault_address,
+                    self.expires_after,
+                    self.http_client.is_mainnet(),
+                )?;
+                sign_typed_data(&envelope, wallet).await?
+            }
+            _ => {
+                let connection_id = action.hash(nonce, self.vault_address, self.expires_after)?;
+                sign_l1_action(wallet, connection_id, self.http_client.is_mainnet()).await?
+            }
+        };
+        let vault_address = action.payload_vault_address(self.vault_address);
+        let expires_after = action.payload_expires_after(self.expires_after);
+        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
+
+        Ok(ExchangePayload {
             action,
             signature,
             nonce,
-            vault_address: self.vault_address,
-        };
-        let res = serde_json::to_string(&exchange_payload)
-            .map_err(|e| Error::JsonParse(e.to_string()))?;
-        debug!("Sending request {res:?}");
+            vault_address,
+            expires_after,
+        })
+    }
 
-        let output = &self
-            .http_client
-            .post("/exchange", res)
+    async fn sign_and_post<T: DeserializeOwned>(
+        &self,
+        action: Actions,
+        nonce: u64,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus<T>> {
+        self.sign_action(action, nonce, wallet)
+            .await?
+            .post(&self.http_client)
             .await
-            .map_err(|e| Error::JsonParse(e.to_string()))?;
-        serde_json::from_str(output).map_err(|e| Error::JsonParse(e.to_string()))
     }
 
-    pub async fn usdc_transfer(
+    /// Submits an action prepared by `UnsignedTransactionBuilder` together with the
+    /// signature produced externally over `components.digest_to_sign`.
+    ///
+    /// The vault address recorded in the components is used, not the one configured
+    /// on this client, since it is part of the signed hash.
+    pub async fn submit_signed(
         &self,
-        amount: &str,
-        destination: &str,
-        wallet: Option<&LocalWallet>,
+        components: UnsignedTransactionComponents,
+        signature: Signature,
     ) -> Result<ExchangeResponseStatus> {
-        let wallet = wallet.unwrap_or(&self.wallet);
-        let hyperliquid_chain = if self.http_client.is_mainnet() {
-            "Mainnet".to_string()
-        } else {
-            "Testnet".to_string()
-        };
+        self.submit_signed_as(components, signature).await
+    }
 
-        let timestamp = next_nonce();
-        let usd_send = UsdSend {
-            signature_chain_id: 421614.into(),
-            hyperliquid_chain,
-            destination: destination.to_string(),
-            amount: amount.to_string(),
-            time: timestamp,
-        };
-        let signature = sign_typed_data(&usd_send, wallet)?;
-        let action = serde_json::to_value(Actions::UsdSend(usd_send))
-            .map_err(|e| Error::JsonParse(e.to_string()))?;
+    /// `submit_signed` for actions with their own response type, e.g.
+    /// `TypedExchangeResponse<H160>` for `createSubAccount`
+    pub async fn submit_signed_as<T: DeserializeOwned>(
+        &self,
+        components: UnsignedTransactionComponents,
+        signature: Signature,
+    ) -> Result<ExchangeResponseStatus<T>> {
+        let payload = components.into_exchange_payload(signature)?;
+        check_nonce_window(payload.nonce, self.nonce_manager.now_ms())?;
+        payload.post(&self.http_client).await
+    }
 
-        self.post(action, signature, timestamp).await
+    pub async fn usdc_transfer(
+        &self,
+        amount: &str,
+        destination: &str,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus<DefaultResponse>> {
+        let timestamp = self.next_nonce(wallet)?;
+        let action = self.with_actions(|actions| actions.usd_send(amount, destination, timestamp));
+        self.sign_and_post(action, timestamp, wallet).await
     }
 
+    /// Moves `usdc` between the spot and perp balances (`usdClassTransfer`). With a vault
+    /// address configured, the balances moved are those of that sub-account or vault.
     pub async fn class_transfer(
         &self,
         usdc: f64,
         to_perp: bool,
-        wallet: Option<&LocalWallet>,
-    ) -> Result<ExchangeResponseStatus> {
-        // payload expects usdc without decimals
-        let usdc = (usdc * 1e6).round() as u64;
-        let wallet = wallet.unwrap_or(&self.wallet);
-
-        let timestamp = next_nonce();
-
-        let action = Actions::SpotUser(SpotUser {
-            class_transfer: ClassTransfer { usdc, to_perp },
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus<DefaultResponse>> {
+        let timestamp = self.next_nonce(wallet)?;
+        let action = self.with_actions(|actions| {
+            actions.usd_class_transfer(usdc, to_perp, self.vault_address, timestamp)
         });
-        let connection_id = action.hash(timestamp, self.vault_address)?;
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
-        let is_mainnet = self.http_client.is_mainnet();
-        let signature = sign_l1_action(wallet, connection_id, is_mainnet)?;
-
-        self.post(action, signature, timestamp).await
+        self.sign_and_post(action, timestamp, wallet).await
     }
 
     pub async fn vault_transfer(
@@ -205,32 +623,65 @@ impl ExchangeClient {
         is_deposit: bool,
         usd: u64,
         vault_address: Option<H160>,
-        wallet: Option<&LocalWallet>,
-    ) -> Result<ExchangeResponseStatus> {
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus<DefaultResponse>> {
         let vault_address = self
             .vault_address
             .or(vault_address)
             .ok_or(Error::VaultAddressNotFound)?;
-        let wallet = wallet.unwrap_or(&self.wallet);
+        let action =
+            self.with_actions(|actions| actions.vault_transfer(vault_address, is_deposit, usd));
+        self.sign_and_post(action, self.next_nonce(wallet)?, wallet)
+            .await
+    }
 
-        let timestamp = next_nonce();
+    /// Creates a vault led by the wallet's account, responding with the vault address.
+    /// `initial_usd` is USDC with 6 decimals.
+    pub async fn create_vault(
+        &self,
+        name: String,
+        description: String,
+        initial_usd: u64,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus<TypedExchangeResponse<H160>>> {
+        let timestamp = self.next_nonce(wallet)?;
+        let action = self.with_actions(|actions| {
+            actions.create_vault(name, description, initial_usd, timestamp)
+        });
+        self.sign_and_post(action, timestamp, wallet).await
+    }
 
-        let action = Actions::VaultTransfer(VaultTransfer {
-            vault_address,
-            is_deposit,
-            usd,
+    /// Changes whether a led vault accepts deposits and whether it closes positions
+    /// proportionally on withdrawals. `None` leaves a setting unchanged.
+    pub async fn vault_modify(
+        &self,
+        vault_address: H160,
+        allow_deposits: Option<bool>,
+        always_close_on_withdraw: Option<bool>,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        let action = self.with_actions(|actions| {
+            actions.vault_modify(vault_address, allow_deposits, always_close_on_withdraw)
         });
-        let connection_id = action.hash(timestamp, self.vault_address)?;
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
-        let is_mainnet = self.http_client.is_mainnet();
-        let signature = sign_l1_action(wallet, connection_id, is_mainnet)?;
+        self.sign_and_post(action, self.next_nonce(wallet)?, wallet)
+            .await
+    }
 
-        self.post(action, signature, timestamp).await
+    /// Distributes `usd` (USDC with 6 decimals) of a led vault's equity to its followers
+    pub async fn vault_distribute(
+        &self,
+        vault_address: H160,
+        usd: u64,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        let action = self.with_actions(|actions| actions.vault_distribute(vault_address, usd));
+        self.sign_and_post(action, self.next_nonce(wallet)?, wallet)
+            .await
     }
 
     pub async fn market_open(
         &self,
-        params: MarketOrderParams<'_>,
+        params: MarketOrderParams<'_, S>,
     ) -> Result<ExchangeResponseStatus> {
         let slippage = params.slippage.unwrap_or(0.05); // Default 5% slippage
         let (px, sz_decimals) = self
@@ -254,7 +705,7 @@ impl ExchangeClient {
 
     pub async fn market_open_with_builder(
         &self,
-        params: MarketOrderParams<'_>,
+        params: MarketOrderParams<'_, S>,
         builder: BuilderInfo,
     ) -> Result<ExchangeResponseStatus> {
         let slippage = params.slippage.unwrap_or(0.05); // Default 5% slippage
@@ -279,18 +730,15 @@ impl ExchangeClient {
 
     pub async fn market_close(
         &self,
-        params: MarketCloseParams<'_>,
+        params: MarketCloseParams<'_, S>,
     ) -> Result<ExchangeResponseStatus> {
         let slippage = params.slippage.unwrap_or(0.05); // Default 5% slippage
         let wallet = params.wallet.unwrap_or(&self.wallet);
 
-        let base_url = match self.http_client.base_url.as_str() {
-            "https://api.hyperliquid.xyz" => BaseUrl::Mainnet,
-            "https://api.hyperliquid-testnet.xyz" => BaseUrl::Testnet,
-            _ => return Err(Error::GenericRequest("Invalid base URL".to_string())),
-        };
-        let info_client = InfoClient::new(None, Some(base_url)).await?;
-        let user_state = info_client.user_state(wallet.address()).await?;
+        let user_state = self
+            .info_client
+            .dex_user_state(self.user_address(Some(wallet)), self.perp_dex(params.asset))
+            .await?;
 
         let position = user_state
             .asset_positions
@@ -325,6 +773,14 @@ impl ExchangeClient {
         self.order(order, Some(wallet)).await
     }
 
+    fn perp_dex(&self, coin: &str) -> Option<String> {
+        self.meta().perp_dex(coin)
+    }
+
+    fn perp_dexs(&self) -> Vec<Option<String>> {
+        self.meta().perp_dexs()
+    }
+
     async fn calculate_slippage_price(
         &self,
         asset: &str,
@@ -332,34 +788,45 @@ impl ExchangeClient {
         slippage: f64,
         px: Option<f64>,
     ) -> Result<(f64, u32)> {
-        let base_url = match self.http_client.base_url.as_str() {
-            "https://api.hyperliquid.xyz" => BaseUrl::Mainnet,
-            "https://api.hyperliquid-testnet.xyz" => BaseUrl::Testnet,
-            _ => return Err(Error::GenericRequest("Invalid base URL".to_string())),
-        };
-        let info_client = InfoClient::new(None, Some(base_url)).await?;
-        let meta = info_client.meta().await?;
-
-        let asset_meta = meta
-            .universe
-            .iter()
-            .find(|a| a.name == asset)
-            .ok_or(Error::AssetNotFound)?;
-
-        let sz_decimals = asset_meta.sz_decimals;
-        let max_decimals: u32 = if self.coin_to_asset[asset] < 10000 {
-            6
-        } else {
-            8
+        // Spot prices allow 8 decimals and perp prices 6, on every dex, minus `sz_decimals`
+        let (sz_decimals, max_decimals, dex, mid_coin) = {
+            let meta = self.meta();
+            let &asset_id = meta.coin_to_asset.get(asset).ok_or(Error::AssetNotFound)?;
+            if is_spot_asset(asset_id) {
+                let pair = meta
+                    .spot_meta
+                    .universe
+                    .iter()
+                    .find(|pair| 10000 + pair.index as u32 == asset_id)
+                    .ok_or(Error::AssetNotFound)?;
+                let base = meta
+                    .spot_meta
+                    .tokens
+                    .iter()
+                    .find(|token| token.index == pair.tokens[0])
+                    .ok_or(Error::AssetNotFound)?;
+                (base.sz_decimals as u32, 8u32, None, pair.name.clone())
+            } else {
+                let asset_meta = meta
+                    .perp_universe()
+                    .find(|a| a.name == asset)
+                    .ok_or(Error::AssetNotFound)?;
+                (
+                    asset_meta.sz_decimals,
+                    6,
+                    meta.perp_dex(asset),
+                    asset.to_string(),
+                )
+            }
         };
         let price_decimals = max_decimals.saturating_sub(sz_decimals);
 
         let px = if let Some(px) = px {
             px
         } else {
-            let all_mids = info_client.all_mids().await?;
+            let all_mids = self.info_client.dex_all_mids(dex).await?;
             all_mids
-                .get(asset)
+                .get(&mid_coin)
                 .ok_or(Error::AssetNotFound)?
                 .parse::<f64>()
                 .map_err(|_| Error::FloatStringParse)?
@@ -383,7 +850,7 @@ impl ExchangeClient {
     pub async fn order(
         &self,
         order: ClientOrderRequest,
-        wallet: Option<&LocalWallet>,
+        wallet: Option<&S>,
     ) -> Result<ExchangeResponseStatus> {
         self.bulk_order(vec![order], wallet).await
     }
@@ -391,7 +858,7 @@ impl ExchangeClient {
     pub async fn order_with_builder(
         &self,
         order: ClientOrderRequest,
-        wallet: Option<&LocalWallet>,
+        wallet: Option<&S>,
         builder: BuilderInfo,
     ) -> Result<ExchangeResponseStatus> {
         self.bulk_order_with_builder(vec![order], wallet, builder)
@@ -401,64 +868,202 @@ impl ExchangeClient {
     pub async fn bulk_order(
         &self,
         orders: Vec<ClientOrderRequest>,
-        wallet: Option<&LocalWallet>,
+        wallet: Option<&S>,
     ) -> Result<ExchangeResponseStatus> {
-        let wallet = wallet.unwrap_or(&self.wallet);
-        let timestamp = next_nonce();
-
-        let mut transformed_orders = Vec::new();
-
-        for order in orders {
-            transformed_orders.push(order.convert(&self.coin_to_asset)?);
-        }
-
-        let action = Actions::Order(BulkOrder {
-            orders: transformed_orders,
-            grouping: "na".to_string(),
-            builder: None,
-        });
-        let connection_id = action.hash(timestamp, self.vault_address)?;
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
-
-        let is_mainnet = self.http_client.is_mainnet();
-        let signature = sign_l1_action(wallet, connection_id, is_mainnet)?;
-        self.post(action, signature, timestamp).await
+        let action =
+            self.with_actions(|actions| actions.order(orders, Grouping::Na.into(), None))?;
+        self.sign_and_post(action, self.next_nonce(wallet)?, wallet)
+            .await
     }
 
     pub async fn bulk_order_with_builder(
         &self,
         orders: Vec<ClientOrderRequest>,
-        wallet: Option<&LocalWallet>,
-        mut builder: BuilderInfo,
+        wallet: Option<&S>,
+        builder: BuilderInfo,
     ) -> Result<ExchangeResponseStatus> {
-        let wallet = wallet.unwrap_or(&self.wallet);
-        let timestamp = next_nonce();
+        let action =
+            self.with_actions(|actions| actions.order(orders, Grouping::Na.into(), Some(builder)))?;
+        self.sign_and_post(action, self.next_nonce(wallet)?, wallet)
+            .await
+    }
+
+    /// `order` with the status checked and matched to the order, see `OrderResult`
+    pub async fn order_result(
+        &self,
+        order: ClientOrderRequest,
+        wallet: Option<&S>,
+    ) -> Result<OrderResult> {
+        let mut results = self.bulk_order_results(vec![order], wallet).await?;
+        Ok(results.remove(0))
+    }
 
-        builder.builder = builder.builder.to_lowercase();
+    /// `bulk_order` with each status matched to its order. A rejection of the whole action
+    /// is returned as `Error::Exchange`, that of a single order in its `OrderResult`.
+    pub async fn bulk_order_results(
+        &self,
+        orders: Vec<ClientOrderRequest>,
+        wallet: Option<&S>,
+    ) -> Result<Vec<OrderResult>> {
+        self.bulk_order(orders.clone(), wallet)
+            .await?
+            .into_result()?
+            .order_results(orders)
+    }
+
+    /// Places the entry and TP/SL legs of `bracket` as one `normalTpsl` bulk order
+    pub async fn bracket_order(
+        &self,
+        bracket: BracketOrder,
+        wallet: Option<&S>,
+    ) -> Result<BracketOrderResult> {
+        let legs = bracket.legs(self.meta().perp_universe())?;
+        let action = self.with_actions(|actions| {
+            actions.order(legs.clone(), Grouping::NormalTpsl.into(), None)
+        })?;
+        let response: ExchangeResponseStatus = self
+            .sign_and_post(action, self.next_nonce(wallet)?, wallet)
+            .await?;
+        let results = response.into_result()?.order_results(legs)?;
+        bracket.results(results)
+    }
 
-        let mut transformed_orders = Vec::new();
+    /// Attaches a take-profit and/or stop-loss to the whole `asset` position. Unlike the legs
+    /// of a bracket order they resize with the position. Use `move_position_tpsl` to change
+    /// the trigger price of an existing one.
+    pub async fn set_position_tpsl(
+        &self,
+        asset: &str,
+        take_profit: Option<f64>,
+        stop_loss: Option<f64>,
+        wallet: Option<&S>,
+    ) -> Result<Vec<OrderResult>> {
+        let user = self.user_address(wallet);
+        let user_state = self
+            .info_client
+            .dex_user_state(user, self.perp_dex(asset))
+            .await?;
+        let szi = match user_state
+            .asset_positions
+            .iter()
+            .find(|p| p.position.coin == asset)
+        {
+            Some(position) => position
+                .position
+                .szi
+                .parse::<f64>()
+                .map_err(|_| Error::FloatStringParse)?,
+            None => 0.0,
+        };
+        if szi == 0.0 {
+            return Err(Error::GenericRequest(format!("No open {asset} position")));
+        }
 
-        for order in orders {
-            transformed_orders.push(order.convert(&self.coin_to_asset)?);
+        let orders: Vec<ClientOrderRequest> = [("tp", take_profit), ("sl", stop_loss)]
+            .into_iter()
+            .filter_map(|(tpsl, trigger_px)| {
+                trigger_px.map(|trigger_px| position_tpsl_order(asset, szi < 0.0, trigger_px, tpsl))
+            })
+            .collect();
+        if orders.is_empty() {
+            return Err(Error::GenericRequest(
+                "Set a take profit or a stop loss".to_string(),
+            ));
         }
 
-        let action = Actions::Order(BulkOrder {
-            orders: transformed_orders,
-            grouping: "na".to_string(),
-            builder: Some(builder),
+        let action = self.with_actions(|actions| {
+            actions.order(orders.clone(), Grouping::PositionTpsl.into(), None)
+        })?;
+        let response: ExchangeResponseStatus = self
+            .sign_and_post(action, self.next_nonce(wallet)?, wallet)
+            .await?;
+        response.into_result()?.order_results(orders)
+    }
+
+    /// Moves the trigger price of the position's `tpsl` ("tp" or "sl") order
+    pub async fn move_position_tpsl(
+        &self,
+        asset: &str,
+        tpsl: &str,
+        trigger_px: f64,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        let order = self
+            .position_tpsl_orders(asset, Some(tpsl), wallet)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| Error::GenericRequest(format!("No {tpsl} on the {asset} position")))?;
+        let is_market = order
+            .order_type
+            .as_deref()
+            .is_some_and(|order_type| order_type.ends_with("Market"));
+
+        let mut moved = position_tpsl_order(asset, order.side == "B", trigger_px, tpsl);
+        moved.sz = order.sz.parse().map_err(|_| Error::FloatStringParse)?;
+        moved.order_type = ClientOrder::Trigger(ClientTrigger {
+            is_market,
+            trigger_px,
+            tpsl: tpsl.to_string(),
         });
-        let connection_id = action.hash(timestamp, self.vault_address)?;
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
+        self.modify(
+            ClientModifyRequest {
+                oid: order.oid,
+                order: moved,
+            },
+            wallet,
+        )
+        .await
+    }
+
+    /// Cancels the position's TP/SL orders, only those of kind `tpsl` ("tp" or "sl") if set
+    pub async fn cancel_position_tpsl(
+        &self,
+        asset: &str,
+        tpsl: Option<&str>,
+        wallet: Option<&S>,
+    ) -> Result<Vec<CancelResult>> {
+        let cancels: Vec<ClientCancelRequest> = self
+            .position_tpsl_orders(asset, tpsl, wallet)
+            .await?
+            .into_iter()
+            .map(|order| ClientCancelRequest {
+                asset: asset.to_string(),
+                oid: order.oid,
+            })
+            .collect();
+        if cancels.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.bulk_cancel_results(cancels, wallet).await
+    }
+
+    async fn position_tpsl_orders(
+        &self,
+        asset: &str,
+        tpsl: Option<&str>,
+        wallet: Option<&S>,
+    ) -> Result<Vec<OpenOrdersResponse>> {
+        let orders = self
+            .info_client
+            .dex_frontend_open_orders(self.user_address(wallet), self.perp_dex(asset))
+            .await?;
+        Ok(orders
+            .into_iter()
+            .filter(|order| is_position_tpsl(order, asset, tpsl))
+            .collect())
+    }
 
-        let is_mainnet = self.http_client.is_mainnet();
-        let signature = sign_l1_action(wallet, connection_id, is_mainnet)?;
-        self.post(action, signature, timestamp).await
+    /// The account whose orders and positions the client's actions apply to
+    fn user_address(&self, wallet: Option<&S>) -> H160 {
+        self.vault_address
+            .unwrap_or_else(|| wallet.unwrap_or(&self.wallet).address())
     }
 
     pub async fn cancel(
         &self,
         cancel: ClientCancelRequest,
-        wallet: Option<&LocalWallet>,
+        wallet: Option<&S>,
     ) -> Result<ExchangeResponseStatus> {
         self.bulk_cancel(vec![cancel], wallet).await
     }
@@ -466,75 +1071,254 @@ impl ExchangeClient {
     pub async fn bulk_cancel(
         &self,
         cancels: Vec<ClientCancelRequest>,
-        wallet: Option<&LocalWallet>,
+        wallet: Option<&S>,
     ) -> Result<ExchangeResponseStatus> {
-        let wallet = wallet.unwrap_or(&self.wallet);
-        let timestamp = next_nonce();
-
-        let mut transformed_cancels = Vec::new();
-        for cancel in cancels.into_iter() {
-            let &asset = self
-                .coin_to_asset
-                .get(&cancel.asset)
-                .ok_or(Error::AssetNotFound)?;
-            transformed_cancels.push(CancelRequest {
-                asset,
-                oid: cancel.oid,
-            });
-        }
-
-        let action = Actions::Cancel(BulkCancel {
-            cancels: transformed_cancels,
-        });
-        let connection_id = action.hash(timestamp, self.vault_address)?;
-
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
-        let is_mainnet = self.http_client.is_mainnet();
-        let signature = sign_l1_action(wallet, connection_id, is_mainnet)?;
-
-        self.post(action, signature, timestamp).await
+        let action = self.with_actions(|actions| actions.cancel(cancels))?;
+        self.sign_and_post(action, self.next_nonce(wallet)?, wallet)
+            .await
     }
 
-    pub async fn modify(
+    /// `bulk_cancel` with each status matched to its cancel
+    pub async fn bulk_cancel_results(
         &self,
-        modify: ClientModifyRequest,
-        wallet: Option<&LocalWallet>,
-    ) -> Result<ExchangeResponseStatus> {
-        self.bulk_modify(vec![modify], wallet).await
+        cancels: Vec<ClientCancelRequest>,
+        wallet: Option<&S>,
+    ) -> Result<Vec<CancelResult>> {
+        self.bulk_cancel(cancels.clone(), wallet)
+            .await?
+            .into_result()?
+            .cancel_results(cancels)
     }
 
-    pub async fn bulk_modify(
+    /// Cancels every open order passing `filter`, of the vault if the client has one. Each
+    /// batch of cancels is sent separately so a rejected batch does not stop the others.
+    pub async fn cancel_all(
         &self,
-        modifies: Vec<ClientModifyRequest>,
-        wallet: Option<&LocalWallet>,
-    ) -> Result<ExchangeResponseStatus> {
-        let wallet = wallet.unwrap_or(&self.wallet);
-        let timestamp = next_nonce();
+        filter: &AssetFilter,
+        wallet: Option<&S>,
+    ) -> Result<CancelAllReport> {
+        let user = self.user_address(wallet);
+        let info_client = &self.info_client;
+        let mut open_orders = Vec::new();
+        let mut seen = HashSet::new();
+        for dex in self.perp_dexs() {
+            for order in info_client.dex_open_orders(user, dex).await? {
+                if seen.insert(order.oid) {
+                    open_orders.push(order);
+                }
+            }
+        }
 
-        let mut transformed_modifies = Vec::new();
-        for modify in modifies.into_iter() {
-            transformed_modifies.push(ModifyRequest {
-                oid: modify.oid,
-                order: modify.order.convert(&self.coin_to_asset)?,
-            });
+        let mut report = CancelAllReport::default();
+        let mut cancels = Vec::new();
+        // Released before the cancels are sent
+        {
+            let meta = self.meta();
+            for order in open_orders {
+                let Some(&asset) = meta.coin_to_asset.get(&order.coin) else {
+                    if filter.matches(false, &[&order.coin]) {
+                        report.results.push(CancelResult {
+                            asset: order.coin,
+                            oid: order.oid,
+                            result: Err(ExchangeError::Other("Unknown asset".to_string())),
+                        });
+                    }
+                    continue;
+                };
+                if filter.matches(is_spot_asset(asset), &[&order.coin]) {
+                    cancels.push(ClientCancelRequest {
+                        asset: order.coin,
+                        oid: order.oid,
+                    });
+                }
+            }
         }
 
-        let action = Actions::BatchModify(BulkModify {
-            modifies: transformed_modifies,
-        });
-        let connection_id = action.hash(timestamp, self.vault_address)?;
+        for batch in cancels.chunks(KILL_SWITCH_BATCH_SIZE) {
+            match self.bulk_cancel_results(batch.to_vec(), wallet).await {
+                Ok(results) => report.results.extend(results),
+                Err(e) => {
+                    let error = batch_error(e);
+                    report
+                        .results
+                        .extend(batch.iter().map(|cancel| CancelResult {
+                            asset: cancel.asset.clone(),
+                            oid: cancel.oid,
+                            result: Err(error.clone()),
+                        }));
+                }
+            }
+        }
+        Ok(report)
+    }
 
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
-        let is_mainnet = self.http_client.is_mainnet();
-        let signature = sign_l1_action(wallet, connection_id, is_mainnet)?;
+    /// Closes every perp position and sells every spot balance into USDC that passes
+    /// `filter`, with Ioc orders `slippage` (5% by default) through the mid. Cancel open
+    /// orders first with `cancel_all`, as they may hold the balances being sold.
+    pub async fn flatten_all(
+        &self,
+        filter: &AssetFilter,
+        slippage: Option<f64>,
+        wallet: Option<&S>,
+    ) -> Result<FlattenReport> {
+        let slippage = slippage.unwrap_or(0.05);
+        let user = self.user_address(wallet);
+        let info_client = &self.info_client;
+        let dexs = if filter.includes_perps() {
+            self.perp_dexs()
+        } else {
+            vec![None]
+        };
+        let mut mids = HashMap::new();
+        for dex in &dexs {
+            mids.extend(info_client.dex_all_mids(dex.clone()).await?);
+        }
+        let mid = |coin: &str| -> std::result::Result<f64, String> {
+            mids.get(coin)
+                .ok_or_else(|| "No mid price".to_string())?
+                .parse::<f64>()
+                .map_err(|e| e.to_string())
+        };
+
+        let mut report = FlattenReport::default();
+        let mut closes = Vec::new();
+        if filter.includes_perps() {
+            let mut positions = Vec::new();
+            for dex in dexs {
+                positions.extend(info_client.dex_user_state(user, dex).await?.asset_positions);
+            }
+            let meta = self.meta();
+            for position in positions {
+                let coin = position.position.coin;
+                if !filter.matches(false, &[&coin]) {
+                    continue;
+                }
+                let close = || -> std::result::Result<Option<ClientOrderRequest>, String> {
+                    let szi = position
+                        .position
+                        .szi
+                        .parse::<f64>()
+                        .map_err(|e| e.to_string())?;
+                    if szi == 0.0 {
+                        return Ok(None);
+                    }
+                    let asset_meta = meta
+                        .perp_universe()
+                        .find(|asset| asset.name == coin)
+                        .ok_or_else(|| "Unknown asset".to_string())?;
+                    Ok(Some(close_order(
+                        &coin,
+                        szi < 0.0,
+                        szi.abs(),
+                        mid(&coin)?,
+                        slippage,
+                        6u32.saturating_sub(asset_meta.sz_decimals),
+                        true,
+                    )))
+                };
+                match close() {
+                    Ok(Some(order)) => closes.push(order),
+                    Ok(None) => {}
+                    Err(reason) => report.skipped.push((coin, reason)),
+                }
+            }
+        }
 
-        self.post(action, signature, timestamp).await
+        if filter.includes_spot() {
+            let balances = info_client.user_token_balances(user).await?.balances;
+            let meta = self.meta();
+            let spot_meta = &meta.spot_meta;
+            let usdc = spot_meta.tokens.iter().find(|token| token.name == "USDC");
+            for balance in balances {
+                let Some(token) = spot_meta
+                    .tokens
+                    .iter()
+                    .find(|token| token.name == balance.coin)
+                else {
+                    continue;
+                };
+                if usdc.is_some_and(|usdc| usdc.index == token.index) {
+                    continue;
+                }
+                let pair = usdc.and_then(|usdc| {
+                    spot_meta
+                        .universe
+                        .iter()
+                        .find(|pair| pair.tokens == [token.index, usdc.index])
+                });
+                let usdc_pair = format!("{}/USDC", token.name);
+                let mut names = vec![token.name.as_str(), usdc_pair.as_str()];
+                if let Some(pair) = pair {
+                    names.push(&pair.name);
+                }
+                if !filter.matches(true, &names) {
+                    continue;
+                }
+                let close = || -> std::result::Result<Option<ClientOrderRequest>, String> {
+                    let pair = pair.ok_or_else(|| "No USDC pair".to_string())?;
+                    let total = balance.total.parse::<f64>().map_err(|e| e.to_string())?;
+                    let hold = balance.hold.parse::<f64>().map_err(|e| e.to_string())?;
+                    let sz_decimals = token.sz_decimals as u32;
+                    let sz = floor_to_decimals(total - hold, sz_decimals);
+                    if sz <= 0.0 {
+                        return Ok(None);
+                    }
+                    Ok(Some(close_order(
+                        &pair.name,
+                        false,
+                        sz,
+                        mid(&pair.name)?,
+                        slippage,
+                        8u32.saturating_sub(sz_decimals),
+                        false,
+                    )))
+                };
+                match close() {
+                    Ok(Some(order)) => closes.push(order),
+                    Ok(None) => {}
+                    Err(reason) => report.skipped.push((balance.coin.clone(), reason)),
+                }
+            }
+        }
+
+        for batch in closes.chunks(KILL_SWITCH_BATCH_SIZE) {
+            match self.bulk_order_results(batch.to_vec(), wallet).await {
+                Ok(results) => report.results.extend(results),
+                Err(e) => {
+                    let error = batch_error(e);
+                    report.results.extend(batch.iter().map(|order| OrderResult {
+                        request: order.clone(),
+                        cloid: order.cloid,
+                        status: Err(error.clone()),
+                    }));
+                }
+            }
+        }
+        Ok(report)
+    }
+
+    pub async fn modify(
+        &self,
+        modify: ClientModifyRequest,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        self.bulk_modify(vec![modify], wallet).await
+    }
+
+    pub async fn bulk_modify(
+        &self,
+        modifies: Vec<ClientModifyRequest>,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        let action = self.with_actions(|actions| actions.modify(modifies))?;
+        self.sign_and_post(action, self.next_nonce(wallet)?, wallet)
+            .await
     }
 
     pub async fn cancel_by_cloid(
         &self,
         cancel: ClientCancelRequestCloid,
-        wallet: Option<&LocalWallet>,
+        wallet: Option<&S>,
     ) -> Result<ExchangeResponseStatus> {
         self.bulk_cancel_by_cloid(vec![cancel], wallet).await
     }
@@ -542,33 +1326,23 @@ impl ExchangeClient {
     pub async fn bulk_cancel_by_cloid(
         &self,
         cancels: Vec<ClientCancelRequestCloid>,
-        wallet: Option<&LocalWallet>,
+        wallet: Option<&S>,
     ) -> Result<ExchangeResponseStatus> {
-        let wallet = wallet.unwrap_or(&self.wallet);
-        let timestamp = next_nonce();
-
-        let mut transformed_cancels: Vec<CancelRequestCloid> = Vec::new();
-        for cancel in cancels.into_iter() {
-            let &asset = self
-                .coin_to_asset
-                .get(&cancel.asset)
-                .ok_or(Error::AssetNotFound)?;
-            transformed_cancels.push(CancelRequestCloid {
-                asset,
-                cloid: uuid_to_hex_string(cancel.cloid),
-            });
-        }
-
-        let action = Actions::CancelByCloid(BulkCancelCloid {
-            cancels: transformed_cancels,
-        });
-
-        let connection_id = action.hash(timestamp, self.vault_address)?;
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
-        let is_mainnet = self.http_client.is_mainnet();
-        let signature = sign_l1_action(wallet, connection_id, is_mainnet)?;
+        let action = self.with_actions(|actions| actions.cancel_by_cloid(cancels))?;
+        self.sign_and_post(action, self.next_nonce(wallet)?, wallet)
+            .await
+    }
 
-        self.post(action, signature, timestamp).await
+    /// `bulk_cancel_by_cloid` with each status matched to its cancel
+    pub async fn bulk_cancel_by_cloid_results(
+        &self,
+        cancels: Vec<ClientCancelRequestCloid>,
+        wallet: Option<&S>,
+    ) -> Result<Vec<CancelByCloidResult>> {
+        self.bulk_cancel_by_cloid(cancels.clone(), wallet)
+            .await?
+            .into_result()?
+            .cancel_by_cloid_results(cancels)
     }
 
     pub async fn update_leverage(
@@ -576,56 +1350,29 @@ impl ExchangeClient {
         leverage: u32,
         coin: &str,
         is_cross: bool,
-        wallet: Option<&LocalWallet>,
+        wallet: Option<&S>,
     ) -> Result<ExchangeResponseStatus> {
-        let wallet = wallet.unwrap_or(&self.wallet);
-
-        let timestamp = next_nonce();
-
-        let &asset_index = self.coin_to_asset.get(coin).ok_or(Error::AssetNotFound)?;
-        let action = Actions::UpdateLeverage(UpdateLeverage {
-            asset: asset_index,
-            is_cross,
-            leverage,
-        });
-        let connection_id = action.hash(timestamp, self.vault_address)?;
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
-        let is_mainnet = self.http_client.is_mainnet();
-        let signature = sign_l1_action(wallet, connection_id, is_mainnet)?;
-
-        self.post(action, signature, timestamp).await
+        let action =
+            self.with_actions(|actions| actions.update_leverage(leverage, coin, is_cross))?;
+        self.sign_and_post(action, self.next_nonce(wallet)?, wallet)
+            .await
     }
 
     pub async fn update_isolated_margin(
         &self,
         amount: f64,
         coin: &str,
-        wallet: Option<&LocalWallet>,
+        wallet: Option<&S>,
     ) -> Result<ExchangeResponseStatus> {
-        let wallet = wallet.unwrap_or(&self.wallet);
-
-        let amount = (amount * 1_000_000.0).round() as i64;
-        let timestamp = next_nonce();
-
-        let &asset_index = self.coin_to_asset.get(coin).ok_or(Error::AssetNotFound)?;
-        let action = Actions::UpdateIsolatedMargin(UpdateIsolatedMargin {
-            asset: asset_index,
-            is_buy: true,
-            ntli: amount,
-        });
-        let connection_id = action.hash(timestamp, self.vault_address)?;
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
-        let is_mainnet = self.http_client.is_mainnet();
-        let signature = sign_l1_action(wallet, connection_id, is_mainnet)?;
-
-        self.post(action, signature, timestamp).await
+        let action = self.with_actions(|actions| actions.update_isolated_margin(amount, coin))?;
+        self.sign_and_post(action, self.next_nonce(wallet)?, wallet)
+            .await
     }
 
     pub async fn approve_agent(
         &self,
-        wallet: Option<&LocalWallet>,
+        wallet: Option<&S>,
     ) -> Result<(String, ExchangeResponseStatus)> {
-        let wallet = wallet.unwrap_or(&self.wallet);
         let key = H256::from(generate_random_key()?).encode_hex()[2..].to_string();
 
         let address = key
@@ -633,52 +1380,43 @@ impl ExchangeClient {
             .map_err(|e| Error::PrivateKeyParse(e.to_string()))?
             .address();
 
-        let hyperliquid_chain = if self.http_client.is_mainnet() {
-            "Mainnet".to_string()
-        } else {
-            "Testnet".to_string()
-        };
+        Ok((
+            key,
+            self.approve_agent_address(address, None, wallet).await?,
+        ))
+    }
 
-        let nonce = next_nonce();
-        let approve_agent = ApproveAgent {
-            signature_chain_id: 421614.into(),
-            hyperliquid_chain,
-            agent_address: address,
-            agent_name: None,
-            nonce,
-        };
-        let signature = sign_typed_data(&approve_agent, wallet)?;
-        let action = serde_json::to_value(Actions::ApproveAgent(approve_agent))
-            .map_err(|e| Error::JsonParse(e.to_string()))?;
-        Ok((key, self.post(action, signature, nonce).await?))
+    /// Approves `agent_address` to sign L1 actions for the wallet. The approval replaces the
+    /// agent previously approved under the same `agent_name`, or the unnamed agent, and
+    /// approving the zero address revokes it. See `AgentManager` to keep agent keys.
+    pub async fn approve_agent_address(
+        &self,
+        agent_address: H160,
+        agent_name: Option<String>,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        let nonce = self.next_nonce(wallet)?;
+        let action =
+            self.with_actions(|actions| actions.approve_agent(agent_address, agent_name, nonce));
+        self.sign_and_post(action, nonce, wallet).await
     }
 
     pub async fn withdraw_from_bridge(
         &self,
         amount: &str,
         destination: &str,
-        wallet: Option<&LocalWallet>,
-    ) -> Result<ExchangeResponseStatus> {
-        let wallet = wallet.unwrap_or(&self.wallet);
-        let hyperliquid_chain = if self.http_client.is_mainnet() {
-            "Mainnet".to_string()
-        } else {
-            "Testnet".to_string()
-        };
-
-        let timestamp = next_nonce();
-        let withdraw = Withdraw3 {
-            signature_chain_id: 421614.into(),
-            hyperliquid_chain,
-            destination: destination.to_string(),
-            amount: amount.to_string(),
-            time: timestamp,
-        };
-        let signature = sign_typed_data(&withdraw, wallet)?;
-        let action = serde_json::to_value(Actions::Withdraw3(withdraw))
-            .map_err(|e| Error::JsonParse(e.to_string()))?;
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus<DefaultResponse>> {
+        let timestamp = self.next_nonce(wallet)?;
+        let action = self.with_actions(|actions| actions.withdraw(amount, destination, timestamp));
+        self.sign_and_post(action, timestamp, wallet).await
+    }
 
-        self.post(action, signature, timestamp).await
+    /// Builds the Arbitrum USDC transfer that deposits `amount` (6 decimals) into the
+    /// Hyperliquid bridge. Deposits happen on Arbitrum rather than through `/exchange`, so the
+    /// transaction is returned for the caller to send with their own Arbitrum provider.
+    pub fn bridge_deposit_transaction(&self, amount: U256) -> Result<TransactionRequest> {
+        bridge::create_deposit_transaction(amount, self.http_client.is_mainnet())
     }
 
     pub async fn spot_transfer(
@@ -686,79 +1424,237 @@ impl ExchangeClient {
         amount: &str,
         destination: &str,
         token: &str,
-        wallet: Option<&LocalWallet>,
-    ) -> Result<ExchangeResponseStatus> {
-        let wallet = wallet.unwrap_or(&self.wallet);
-        let hyperliquid_chain = if self.http_client.is_mainnet() {
-            "Mainnet".to_string()
-        } else {
-            "Testnet".to_string()
-        };
-
-        let timestamp = next_nonce();
-        let spot_send = SpotSend {
-            signature_chain_id: 421614.into(),
-            hyperliquid_chain,
-            destination: destination.to_string(),
-            amount: amount.to_string(),
-            time: timestamp,
-            token: token.to_string(),
-        };
-        let signature = sign_typed_data(&spot_send, wallet)?;
-        let action = serde_json::to_value(Actions::SpotSend(spot_send))
-            .map_err(|e| Error::JsonParse(e.to_string()))?;
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus<DefaultResponse>> {
+        let timestamp = self.next_nonce(wallet)?;
+        let action =
+            self.with_actions(|actions| actions.spot_send(amount, destination, token, timestamp));
+        self.sign_and_post(action, timestamp, wallet).await
+    }
 
-        self.post(action, signature, timestamp).await
+    /// Sends `amount` of `token` from the `source_dex` balance to the `destination_dex`
+    /// balance of `destination`, which may be the sender's own address. The main perp dex is
+    /// `""` and the spot balance `"spot"`. With a vault address configured, the funds come
+    /// from that sub-account or vault.
+    pub async fn send_asset(
+        &self,
+        destination: &str,
+        source_dex: &str,
+        destination_dex: &str,
+        token: &str,
+        amount: &str,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus<DefaultResponse>> {
+        let timestamp = self.next_nonce(wallet)?;
+        let action = self.with_actions(|actions| {
+            actions.send_asset(
+                destination,
+                source_dex,
+                destination_dex,
+                token,
+                amount,
+                self.vault_address,
+                timestamp,
+            )
+        });
+        self.sign_and_post(action, timestamp, wallet).await
     }
 
     pub async fn set_referrer(
         &self,
         code: String,
-        wallet: Option<&LocalWallet>,
+        wallet: Option<&S>,
     ) -> Result<ExchangeResponseStatus> {
-        let wallet = wallet.unwrap_or(&self.wallet);
-        let timestamp = next_nonce();
+        let action = self.with_actions(|actions| actions.set_referrer(code));
+        self.sign_and_post(action, self.next_nonce(wallet)?, wallet)
+            .await
+    }
 
-        let action = Actions::SetReferrer(SetReferrer { code });
+    /// Stakes `wei` of HYPE (8 decimals) from the spot balance (`cDeposit`)
+    pub async fn staking_deposit(
+        &self,
+        wei: u64,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        let timestamp = self.next_nonce(wallet)?;
+        let action = self.with_actions(|actions| actions.c_deposit(wei, timestamp));
+        self.sign_and_post(action, timestamp, wallet).await
+    }
 
-        let connection_id = action.hash(timestamp, self.vault_address)?;
-        let action = serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
+    /// Unstakes `wei` of undelegated HYPE (8 decimals) back to the spot balance
+    /// (`cWithdraw`)
+    pub async fn staking_withdraw(
+        &self,
+        wei: u64,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        let timestamp = self.next_nonce(wallet)?;
+        let action = self.with_actions(|actions| actions.c_withdraw(wei, timestamp));
+        self.sign_and_post(action, timestamp, wallet).await
+    }
 
-        let is_mainnet = self.http_client.is_mainnet();
-        let signature = sign_l1_action(wallet, connection_id, is_mainnet)?;
-        self.post(action, signature, timestamp).await
+    /// Delegates `wei` of staked HYPE (8 decimals) to `validator`, or undelegates it
+    pub async fn token_delegate(
+        &self,
+        validator: H160,
+        wei: u64,
+        is_undelegate: bool,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        let timestamp = self.next_nonce(wallet)?;
+        let action = self.with_actions(|actions| {
+            actions.token_delegate(validator, wei, is_undelegate, timestamp)
+        });
+        self.sign_and_post(action, timestamp, wallet).await
+    }
+
+    /// Creates a sub-account of the wallet's account, responding with its address
+    pub async fn create_sub_account(
+        &self,
+        name: String,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus<TypedExchangeResponse<H160>>> {
+        let action = self.with_actions(|actions| actions.create_sub_account(name));
+        self.sign_and_post(action, self.next_nonce(wallet)?, wallet)
+            .await
+    }
+
+    /// Moves `usd` (USDC with 6 decimals) into `sub_account_user`'s perp balance, or out of
+    /// it when `is_deposit` is false
+    pub async fn sub_account_transfer(
+        &self,
+        sub_account_user: H160,
+        is_deposit: bool,
+        usd: u64,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus<DefaultResponse>> {
+        let action = self.with_actions(|actions| {
+            actions.sub_account_transfer(sub_account_user, is_deposit, usd)
+        });
+        self.sign_and_post(action, self.next_nonce(wallet)?, wallet)
+            .await
+    }
+
+    pub async fn sub_account_spot_transfer(
+        &self,
+        sub_account_user: H160,
+        is_deposit: bool,
+        token: &str,
+        amount: &str,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus<DefaultResponse>> {
+        let action = self.with_actions(|actions| {
+            actions.sub_account_spot_transfer(sub_account_user, is_deposit, token, amount)
+        });
+        self.sign_and_post(action, self.next_nonce(wallet)?, wallet)
+            .await
+    }
+
+    /// Places a TWAP order, executed by the exchange in slices. The response status carries
+    /// the id to cancel it with.
+    pub async fn twap_order(
+        &self,
+        twap: ClientTwapRequest,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        let action = self.with_actions(|actions| actions.twap_order(twap))?;
+        self.sign_and_post(action, self.next_nonce(wallet)?, wallet)
+            .await
+    }
+
+    pub async fn twap_cancel(
+        &self,
+        cancel: ClientTwapCancelRequest,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        let action = self.with_actions(|actions| actions.twap_cancel(cancel))?;
+        self.sign_and_post(action, self.next_nonce(wallet)?, wallet)
+            .await
+    }
+
+    /// Schedules all open orders to be cancelled at `time` (ms), which must be at least 5
+    /// seconds away. `None` removes the scheduled cancel. See `DeadMansSwitch` to keep
+    /// pushing it back while the process is healthy.
+    pub async fn schedule_cancel(
+        &self,
+        time: Option<u64>,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        let action = self.with_actions(|actions| actions.schedule_cancel(time));
+        self.sign_and_post(action, self.next_nonce(wallet)?, wallet)
+            .await
     }
 
     pub async fn approve_builder_fee(
         &self,
         builder: String,
         max_fee_rate: String,
-        wallet: Option<&LocalWallet>,
+        wallet: Option<&S>,
     ) -> Result<ExchangeResponseStatus> {
-        let wallet = wallet.unwrap_or(&self.wallet);
-        let timestamp = next_nonce();
-
-        // Ensure builder address is lowercase
-        let builder = builder.to_lowercase();
+        let timestamp = self.next_nonce(wallet)?;
+        let action = self
+            .with_actions(|actions| actions.approve_builder_fee(&builder, max_fee_rate, timestamp));
+        self.sign_and_post(action, timestamp, wallet).await
+    }
 
-        let hyperliquid_chain = if self.http_client.is_mainnet() {
-            "Mainnet".to_string()
-        } else {
-            "Testnet".to_string()
-        };
+    /// Converts the wallet's account into a multi-sig user controlled by `authorized_users`,
+    /// `threshold` of which must sign each of its actions
+    pub async fn convert_to_multi_sig_user(
+        &self,
+        authorized_users: Vec<H160>,
+        threshold: usize,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        let nonce = self.next_nonce(wallet)?;
+        let action = self.with_actions(|actions| {
+            actions.convert_to_multi_sig_user(
+                MultiSigSigners {
+                    authorized_users,
+                    threshold,
+                },
+                nonce,
+            )
+        })?;
+        self.sign_and_post(action, nonce, wallet).await
+    }
 
-        let approve_builder_fee = ApproveBuilderFee {
-            signature_chain_id: 421614.into(),
-            hyperliquid_chain,
-            builder,
-            max_fee_rate,
-            nonce: timestamp,
-        };
-        let signature = sign_typed_data(&approve_builder_fee, wallet)?;
-        let action = serde_json::to_value(Actions::ApproveBuilderFee(approve_builder_fee))
-            .map_err(|e| Error::JsonParse(e.to_string()))?;
+    /// Signs `action` as one of the authorized users of `multi_sig_user`. The signatures
+    /// are collected and passed to `multi_sig` by `outer_signer`, using the same nonce.
+    pub async fn sign_multi_sig_action(
+        &self,
+        multi_sig_user: H160,
+        outer_signer: H160,
+        action: &Actions,
+        nonce: u64,
+        wallet: Option<&S>,
+    ) -> Result<Signature> {
+        let wallet = wallet.unwrap_or(&self.wallet);
+        let digest = action.multi_sig_signing_digest(
+            multi_sig_user,
+            outer_signer,
+            nonce,
+            self.vault_address,
+            self.expires_after,
+            self.http_client.is_mainnet(),
+        )?;
+        wallet.sign_hash(digest).await
+    }
 
-        self.post(action, signature, timestamp).await
+    /// Submits `inner_action` on behalf of `multi_sig_user` with the collected signatures
+    /// of its authorized users. The wallet signing the envelope is the outer signer.
+    pub async fn multi_sig(
+        &self,
+        multi_sig_user: H160,
+        inner_action: Actions,
+        signatures: Vec<Signature>,
+        nonce: u64,
+        wallet: Option<&S>,
+    ) -> Result<ExchangeResponseStatus> {
+        let outer_signer = wallet.unwrap_or(&self.wallet).address();
+        let action = self.with_actions(|actions| {
+            actions.multi_sig(multi_sig_user, outer_signer, inner_action, signatures)
+        });
+        self.sign_and_post(action, nonce, wallet).await
     }
 }
 
@@ -767,7 +1663,41 @@ fn round_to_decimals(value: f64, decimals: u32) -> f64 {
     (value * factor).round() / factor
 }
 
-fn round_to_significant_and_decimal(value: f64, sig_figs: u32, max_decimals: u32) -> f64 {
+/// A reduce-only market trigger closing the whole position at `trigger_px`
+fn position_tpsl_order(
+    asset: &str,
+    is_buy: bool,
+    trigger_px: f64,
+    tpsl: &str,
+) -> ClientOrderRequest {
+    ClientOrderRequest {
+        asset: asset.to_string(),
+        is_buy,
+        reduce_only: true,
+        limit_px: trigger_px,
+        // Sized by the exchange to the position
+        sz: 0.0,
+        cloid: None,
+        order_type: ClientOrder::Trigger(ClientTrigger {
+            is_market: true,
+            trigger_px,
+            tpsl: tpsl.to_string(),
+        }),
+    }
+}
+
+fn is_position_tpsl(order: &OpenOrdersResponse, asset: &str, tpsl: Option<&str>) -> bool {
+    order.is_position_tpsl
+        && order.coin == asset
+        && order.tpsl().is_some()
+        && tpsl.is_none_or(|tpsl| order.tpsl() == Some(tpsl))
+}
+
+pub(crate) fn round_to_significant_and_decimal(
+    value: f64,
+    sig_figs: u32,
+    max_decimals: u32,
+) -> f64 {
     let abs_value = value.abs();
     let magnitude = abs_value.log10().floor() as i32;
     let scale = 10f64.powi(sig_figs as i32 - magnitude - 1);
@@ -781,8 +1711,13 @@ mod tests {
 
     use super::*;
     use crate::{
-        exchange::order::{Limit, OrderRequest, Trigger},
-        Order,
+        exchange::actions::HYPERLIQUID_EIP_PREFIX,
+        exchange::{
+            cancel::CancelRequest,
+            order::{Limit, OrderRequest, Trigger},
+        },
+        helpers::uuid_to_hex_string,
+        ExchangeDataStatus, Order, TwapRequest, TwapRunning,
     };
 
     fn get_wallet() -> Result<LocalWallet> {
@@ -792,8 +1727,8 @@ mod tests {
             .map_err(|e| Error::Wallet(e.to_string()))
     }
 
-    #[test]
-    fn test_limit_order_action_hashing() -> Result<()> {
+    #[tokio::test]
+    async fn test_limit_order_action_hashing() -> Result<()> {
         let wallet = get_wallet()?;
         let action = Actions::Order(BulkOrder {
             orders: vec![OrderRequest {
@@ -810,19 +1745,64 @@ mod tests {
             grouping: "na".to_string(),
             builder: None,
         });
-        let connection_id = action.hash(1583838, None)?;
+        let connection_id = action.hash(1583838, None, None)?;
 
-        let signature = sign_l1_action(&wallet, connection_id, true)?;
+        let signature = sign_l1_action(&wallet, connection_id, true).await?;
         assert_eq!(signature.to_string(), "77957e58e70f43b6b68581f2dc42011fc384538a2e5b7bf42d5b936f19fbb67360721a8598727230f67080efee48c812a6a4442013fd3b0eed509171bef9f23f1c");
 
-        let signature = sign_l1_action(&wallet, connection_id, false)?;
+        let signature = sign_l1_action(&wallet, connection_id, false).await?;
         assert_eq!(signature.to_string(), "cd0925372ff1ed499e54883e9a6205ecfadec748f80ec463fe2f84f1209648776377961965cb7b12414186b1ea291e95fd512722427efcbcfb3b0b2bcd4d79d01c");
 
         Ok(())
     }
 
-    #[test]
-    fn test_limit_order_action_hashing_with_cloid() -> Result<()> {
+    #[tokio::test]
+    async fn test_limit_order_action_hashing_with_expires_after() -> Result<()> {
+        let wallet = get_wallet()?;
+        let action = Actions::Order(BulkOrder {
+            orders: vec![OrderRequest {
+                asset: 1,
+                is_buy: true,
+                limit_px: "2000.0".to_string(),
+                sz: "3.5".to_string(),
+                reduce_only: false,
+                order_type: Order::Limit(Limit {
+                    tif: "Ioc".to_string(),
+                }),
+                cloid: None,
+            }],
+            grouping: "na".to_string(),
+            builder: None,
+        });
+        let vault_address = H160::from_str("0x1719884eb866cb12b2287399b15f7db5e7d775ea")
+            .map_err(|e| Error::GenericParse(e.to_string()))?;
+        let expires_after = Some(1583838 + 60_000);
+
+        // scripts/signing_vectors.py checks these vectors against the Python SDK's
+        // `action_hash` and `sign_l1_action`
+        let connection_id = action.hash(1583838, None, expires_after)?;
+        assert_eq!(
+            connection_id,
+            H256::from_str("0xf833db1e228f1a7717665cbff532260a4965bf8ff84683f86b230a7d1ea44a77")
+                .map_err(|e| Error::GenericParse(e.to_string()))?
+        );
+        assert_ne!(connection_id, action.hash(1583838, None, None)?);
+        let signature = sign_l1_action(&wallet, connection_id, true).await?;
+        assert_eq!(signature.to_string(), "07de8aebc5176038fb9545d50c6a55ddbad58b6b70796eb8d07d672c14576fdc4d5f062d2ea51d421acc37fa88340e98864a68720c0e85be3e06bdedb107bfc81b");
+
+        let connection_id = action.hash(1583838, Some(vault_address), expires_after)?;
+        assert_ne!(
+            connection_id,
+            action.hash(1583838, Some(vault_address), None)?
+        );
+        let signature = sign_l1_action(&wallet, connection_id, true).await?;
+        assert_eq!(signature.to_string(), "3467006af497bdfa9c739e4780dd372927b78559c00bd2294430d065ca8502d553a7a200dd600a8664339e9887d6306e4d7c022c624c2a18ed01775ca0367e121b");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_limit_order_action_hashing_with_cloid() -> Result<()> {
         let cloid = uuid::Uuid::from_str("1e60610f-0b3d-4205-97c8-8c1fed2ad5ee")
             .map_err(|_e| uuid::Uuid::new_v4());
         let wallet = get_wallet()?;
@@ -841,19 +1821,19 @@ mod tests {
             grouping: "na".to_string(),
             builder: None,
         });
-        let connection_id = action.hash(1583838, None)?;
+        let connection_id = action.hash(1583838, None, None)?;
 
-        let signature = sign_l1_action(&wallet, connection_id, true)?;
+        let signature = sign_l1_action(&wallet, connection_id, true).await?;
         assert_eq!(signature.to_string(), "d3e894092eb27098077145714630a77bbe3836120ee29df7d935d8510b03a08f456de5ec1be82aa65fc6ecda9ef928b0445e212517a98858cfaa251c4cd7552b1c");
 
-        let signature = sign_l1_action(&wallet, connection_id, false)?;
+        let signature = sign_l1_action(&wallet, connection_id, false).await?;
         assert_eq!(signature.to_string(), "3768349dbb22a7fd770fc9fc50c7b5124a7da342ea579b309f58002ceae49b4357badc7909770919c45d850aabb08474ff2b7b3204ae5b66d9f7375582981f111c");
 
         Ok(())
     }
 
-    #[test]
-    fn test_tpsl_order_action_hashing() -> Result<()> {
+    #[tokio::test]
+    async fn test_tpsl_order_action_hashing() -> Result<()> {
         for (tpsl, mainnet_signature, testnet_signature) in [
             (
                 "tp",
@@ -886,19 +1866,19 @@ mod tests {
                 grouping: "na".to_string(),
                 builder: None,
             });
-            let connection_id = action.hash(1583838, None)?;
+            let connection_id = action.hash(1583838, None, None)?;
 
-            let signature = sign_l1_action(&wallet, connection_id, true)?;
+            let signature = sign_l1_action(&wallet, connection_id, true).await?;
             assert_eq!(signature.to_string(), mainnet_signature);
 
-            let signature = sign_l1_action(&wallet, connection_id, false)?;
+            let signature = sign_l1_action(&wallet, connection_id, false).await?;
             assert_eq!(signature.to_string(), testnet_signature);
         }
         Ok(())
     }
 
-    #[test]
-    fn test_cancel_action_hashing() -> Result<()> {
+    #[tokio::test]
+    async fn test_cancel_action_hashing() -> Result<()> {
         let wallet = get_wallet()?;
         let action = Actions::Cancel(BulkCancel {
             cancels: vec![CancelRequest {
@@ -906,19 +1886,210 @@ mod tests {
                 oid: 82382,
             }],
         });
-        let connection_id = action.hash(1583838, None)?;
+        let connection_id = action.hash(1583838, None, None)?;
 
-        let signature = sign_l1_action(&wallet, connection_id, true)?;
+        let signature = sign_l1_action(&wallet, connection_id, true).await?;
         assert_eq!(signature.to_string(), "02f76cc5b16e0810152fa0e14e7b219f49c361e3325f771544c6f54e157bf9fa17ed0afc11a98596be85d5cd9f86600aad515337318f7ab346e5ccc1b03425d51b");
 
-        let signature = sign_l1_action(&wallet, connection_id, false)?;
+        let signature = sign_l1_action(&wallet, connection_id, false).await?;
         assert_eq!(signature.to_string(), "6ffebadfd48067663390962539fbde76cfa36f53be65abe2ab72c9db6d0db44457720db9d7c4860f142a484f070c84eb4b9694c3a617c83f0d698a27e55fd5e01c");
 
         Ok(())
     }
 
     #[test]
-    fn test_approve_builder_fee_signing() -> Result<()> {
+    fn test_twap_wire_format() -> Result<()> {
+        let action = Actions::TwapOrder(TwapOrder {
+            twap: TwapRequest {
+                asset: 1,
+                is_buy: true,
+                sz: "10".to_string(),
+                reduce_only: false,
+                minutes: 30,
+                randomize: true,
+            },
+        });
+        assert_eq!(
+            serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?,
+            serde_json::json!({
+                "type": "twapOrder",
+                "twap": {"a": 1, "b": true, "s": "10", "r": false, "m": 30, "t": true},
+            })
+        );
+        let action = Actions::TwapCancel(TwapCancel {
+            asset: 1,
+            twap_id: 7,
+        });
+        assert_eq!(
+            serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?,
+            serde_json::json!({"type": "twapCancel", "a": 1, "t": 7})
+        );
+
+        let response: ExchangeResponseStatus = serde_json::from_str(
+            r#"{"status":"ok","response":{"type":"twapOrder","data":{"status":{"running":{"twapId":77738308}}}}}"#,
+        )
+        .map_err(|e| Error::JsonParse(e.to_string()))?;
+        let ExchangeResponseStatus::Ok(response) = response else {
+            panic!("expected an ok response");
+        };
+        assert!(matches!(
+            response.data.and_then(|data| data.status),
+            Some(ExchangeDataStatus::Running(TwapRunning {
+                twap_id: 77738308
+            }))
+        ));
+
+        let response: ExchangeResponseStatus = serde_json::from_str(
+            r#"{"status":"ok","response":{"type":"twapCancel","data":{"status":{"error":"TWAP was never placed, already canceled, or filled."}}}}"#,
+        )
+        .map_err(|e| Error::JsonParse(e.to_string()))?;
+        let ExchangeResponseStatus::Ok(response) = response else {
+            panic!("expected an ok response");
+        };
+        assert!(matches!(
+            response.data.and_then(|data| data.status),
+            Some(ExchangeDataStatus::Error(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_sub_account_wire_format() -> Result<()> {
+        let sub_account_user: H160 = "0x1d9470d4b963f552e6f671a81619d395877bf409"
+            .parse()
+            .unwrap();
+        let action = Actions::SubAccountTransfer(SubAccountUsdTransfer {
+            sub_account_user,
+            is_deposit: true,
+            usd: 1_000_000,
+        });
+        assert_eq!(
+            serde_json::to_string(&action).map_err(|e| Error::JsonParse(e.to_string()))?,
+            r#"{"type":"subAccountTransfer","subAccountUser":"0x1d9470d4b963f552e6f671a81619d395877bf409","isDeposit":true,"usd":1000000}"#
+        );
+        let action = Actions::SubAccountSpotTransfer(SubAccountSpotTransfer {
+            sub_account_user,
+            is_deposit: false,
+            token: "PURR:0xc4bf3f870c0e9465323c0b6ed28096c2".to_string(),
+            amount: "2.5".to_string(),
+        });
+        assert_eq!(
+            serde_json::to_string(&action).map_err(|e| Error::JsonParse(e.to_string()))?,
+            r#"{"type":"subAccountSpotTransfer","subAccountUser":"0x1d9470d4b963f552e6f671a81619d395877bf409","isDeposit":false,"token":"PURR:0xc4bf3f870c0e9465323c0b6ed28096c2","amount":"2.5"}"#
+        );
+
+        let response: ExchangeResponseStatus<TypedExchangeResponse<H160>> = serde_json::from_str(
+            r#"{"status":"ok","response":{"type":"createSubAccount","data":"0x1d9470d4b963f552e6f671a81619d395877bf409"}}"#,
+        )
+        .map_err(|e| Error::JsonParse(e.to_string()))?;
+        let ExchangeResponseStatus::Ok(response) = response else {
+            panic!("expected an ok response");
+        };
+        assert_eq!(response.data, sub_account_user);
+        Ok(())
+    }
+
+    #[test]
+    fn test_vault_wire_format() -> Result<()> {
+        let vault_address: H160 = "0x1d9470d4b963f552e6f671a81619d395877bf409"
+            .parse()
+            .unwrap();
+        // Unchanged settings are sent as null, not omitted
+        let action = Actions::VaultModify(VaultModify {
+            vault_address,
+            allow_deposits: Some(false),
+            always_close_on_withdraw: None,
+        });
+        assert_eq!(
+            serde_json::to_string(&action).map_err(|e| Error::JsonParse(e.to_string()))?,
+            r#"{"type":"vaultModify","vaultAddress":"0x1d9470d4b963f552e6f671a81619d395877bf409","allowDeposits":false,"alwaysCloseOnWithdraw":null}"#
+        );
+        let action = Actions::CreateVault(CreateVault {
+            name: "vault".to_string(),
+            description: "a vault".to_string(),
+            initial_usd: 100_000_000,
+            nonce: 1583838,
+        });
+        assert_eq!(
+            serde_json::to_string(&action).map_err(|e| Error::JsonParse(e.to_string()))?,
+            r#"{"type":"createVault","name":"vault","description":"a vault","initialUsd":100000000,"nonce":1583838}"#
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_signature_chain_id() {
+        let coin_to_asset = HashMap::new();
+        for (is_mainnet, hyperliquid_chain) in [(true, "Mainnet"), (false, "Testnet")] {
+            let actions = ActionBuilder {
+                coin_to_asset: &coin_to_asset,
+                is_mainnet,
+            };
+            // Mainnet user-signed actions keep the 421614 domain of the official SDKs
+            assert_eq!(actions.signature_chain_id(), U256::from(421614));
+            assert_eq!(actions.hyperliquid_chain(), hyperliquid_chain);
+        }
+    }
+
+    #[test]
+    fn test_usd_class_transfer_wire_format() -> Result<()> {
+        let vault_address: H160 = "0x1d9470d4b963f552e6f671a81619d395877bf409"
+            .parse()
+            .unwrap();
+        let coin_to_asset = HashMap::new();
+        let actions = ActionBuilder {
+            coin_to_asset: &coin_to_asset,
+            is_mainnet: true,
+        };
+        let action = actions.usd_class_transfer(1.5, true, Some(vault_address), 1583838);
+        assert_eq!(
+            serde_json::to_string(&action).map_err(|e| Error::JsonParse(e.to_string()))?,
+            r#"{"type":"usdClassTransfer","signatureChainId":"0x66eee","hyperliquidChain":"Mainnet","amount":"1.5 subaccount:0x1d9470d4b963f552e6f671a81619d395877bf409","toPerp":true,"nonce":1583838}"#
+        );
+        // The sub-account is named in the amount, not as the payload's vault address
+        assert_eq!(action.payload_vault_address(Some(vault_address)), None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_send_asset_wire_format() -> Result<()> {
+        let vault_address: H160 = "0x1d9470d4b963f552e6f671a81619d395877bf409"
+            .parse()
+            .unwrap();
+        let coin_to_asset = HashMap::new();
+        let actions = ActionBuilder {
+            coin_to_asset: &coin_to_asset,
+            is_mainnet: true,
+        };
+        let destination = "0x0d1d9635d0640821d15e323ac8adadfa9c111414";
+        let action = actions.send_asset(destination, "", "spot", "USDC", "10", None, 1583838);
+        assert_eq!(
+            serde_json::to_string(&action).map_err(|e| Error::JsonParse(e.to_string()))?,
+            r#"{"type":"sendAsset","signatureChainId":"0x66eee","hyperliquidChain":"Mainnet","destination":"0x0d1d9635d0640821d15e323ac8adadfa9c111414","sourceDex":"","destinationDex":"spot","token":"USDC","amount":"10","fromSubAccount":"","nonce":1583838}"#
+        );
+
+        let action = actions.send_asset(
+            destination,
+            "",
+            "spot",
+            "USDC",
+            "10",
+            Some(vault_address),
+            1583838,
+        );
+        let Actions::SendAsset(send_asset) = &action else {
+            panic!("expected sendAsset");
+        };
+        assert_eq!(
+            send_asset.from_sub_account,
+            "0x1d9470d4b963f552e6f671a81619d395877bf409"
+        );
+        assert_eq!(action.payload_vault_address(Some(vault_address)), None);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_approve_builder_fee_signing() -> Result<()> {
         let wallet = get_wallet()?;
 
         // Test mainnet
@@ -930,7 +2101,7 @@ mod tests {
             nonce: 1583838,
         };
 
-        let mainnet_signature = sign_typed_data(&mainnet_fee, &wallet)?;
+        let mainnet_signature = sign_typed_data(&mainnet_fee, &wallet).await?;
         assert_eq!(
             mainnet_signature.to_string(),
             "343c9078af7c3d6683abefd0ca3b2960de5b669b716863e6dc49090853a4a3cd6c016301239461091a8ca3ea5ac783362526c4d9e9e624ffc563aea93d6ac2391b"
@@ -945,7 +2116,7 @@ mod tests {
             nonce: 1583838,
         };
 
-        let testnet_signature = sign_typed_data(&testnet_fee, &wallet)?;
+        let testnet_signature = sign_typed_data(&testnet_fee, &wallet).await?;
         assert_eq!(
             testnet_signature.to_string(),
             "2ada43eeebeba9cfe13faf95aa84e5b8c4885c3a07cbf4536f2df5edd340d4eb1ed0e24f60a80d199a842258d5fa737a18d486f7d4e656268b434d226f2811d71c"
@@ -957,8 +2128,8 @@ mod tests {
         Ok(())
     }
 
-    #[test]
-    fn test_approve_builder_fee_hash() -> Result<()> {
+    #[tokio::test]
+    async fn test_approve_builder_fee_hash() -> Result<()> {
         let action = Actions::ApproveBuilderFee(ApproveBuilderFee {
             signature_chain_id: 421614.into(),
             hyperliquid_chain: "Mainnet".to_string(),
@@ -967,7 +2138,7 @@ mod tests {
             nonce: 1583838,
         });
 
-        let connection_id = action.hash(1583838, None)?;
+        let connection_id = action.hash(1583838, None, None)?;
         assert_eq!(
             format!("{:?}", connection_id),
             "0x63d953995809b97e9bfc754917b14d19eeef680dbf7d707e68dfa0b0484bafd2"
@@ -975,4 +2146,227 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_multi_sig_signing_digest() -> Result<()> {
+        let wallet = get_wallet()?;
+        let multi_sig_user = H160::from_low_u64_be(1);
+        let outer_signer = H160::from_low_u64_be(2);
+
+        let usd_send = UsdSend {
+            signature_chain_id: 421614.into(),
+            hyperliquid_chain: "Testnet".to_string(),
+            destination: "0x0d1d9635d0640821d15e323ac8adadfa9c111414".to_string(),
+            amount: "1".to_string(),
+            time: 1583838,
+        };
+        let action = Actions::UsdSend(usd_send.clone());
+        let digest = action.multi_sig_signing_digest(
+            multi_sig_user,
+            outer_signer,
+            1583838,
+            None,
+            None,
+            false,
+        )?;
+
+        // The UsdSend struct with the multi-sig user and outer signer after hyperliquidChain
+        let type_hash = ethers::types::transaction::eip712::make_type_hash(
+            format!("{HYPERLIQUID_EIP_PREFIX}UsdSend"),
+            &[
+                (
+                    "hyperliquidChain".to_string(),
+                    ethers::abi::ParamType::String,
+                ),
+                (
+                    "payloadMultiSigUser".to_string(),
+                    ethers::abi::ParamType::Address,
+                ),
+                ("outerSigner".to_string(), ethers::abi::ParamType::Address),
+                ("destination".to_string(), ethers::abi::ParamType::String),
+                ("amount".to_string(), ethers::abi::ParamType::String),
+                ("time".to_string(), ethers::abi::ParamType::Uint(64)),
+            ],
+        );
+        let struct_hash = ethers::utils::keccak256(ethers::abi::encode(&[
+            ethers::abi::Token::Uint(type_hash.into()),
+            ethers::abi::Token::Uint(ethers::utils::keccak256("Testnet").into()),
+            ethers::abi::Token::Address(multi_sig_user),
+            ethers::abi::Token::Address(outer_signer),
+            ethers::abi::Token::Uint(ethers::utils::keccak256(&usd_send.destination).into()),
+            ethers::abi::Token::Uint(ethers::utils::keccak256("1").into()),
+            ethers::abi::Token::Uint(1583838.into()),
+        ]));
+        let domain_separator = usd_send
+            .domain_separator()
+            .map_err(|e| Error::Eip712(e.to_string()))?;
+        let expected = ethers::utils::keccak256(
+            [&[0x19, 0x01], &domain_separator[..], &struct_hash[..]].concat(),
+        );
+        assert_eq!(digest, H256::from(expected));
+        assert_ne!(digest, action.signing_digest(1583838, None, None, false)?);
+
+        // L1 actions sign the [multiSigUser, outerSigner, action] envelope through l1::Agent
+        let action = Actions::SetReferrer(SetReferrer {
+            code: "TEST".to_string(),
+        });
+        let digest = action.multi_sig_signing_digest(
+            multi_sig_user,
+            outer_signer,
+            1583838,
+            None,
+            None,
+            true,
+        )?;
+        let connection_id = hash_action(
+            &(multi_sig_user, outer_signer, &action),
+            1583838,
+            None,
+            None,
+        )?;
+        let signature = sign_l1_action(&wallet, connection_id, true).await?;
+        assert_eq!(
+            recover_signer(digest, &signature)?,
+            HyperliquidSigner::address(&wallet)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_position_tpsl() {
+        let orders: Vec<OpenOrdersResponse> = serde_json::from_value(serde_json::json!([
+            {
+                "coin": "ETH", "side": "A", "limitPx": "3300.0", "sz": "0.0", "oid": 1,
+                "timestamp": 1700000000000u64, "triggerCondition": "Price above 3300",
+                "isTrigger": true, "triggerPx": "3300.0", "children": [],
+                "isPositionTpsl": true, "reduceOnly": true, "orderType": "Take Profit Market",
+                "origSz": "0.0", "tif": null, "cloid": null
+            },
+            {
+                "coin": "ETH", "side": "A", "limitPx": "2900.0", "sz": "0.0", "oid": 2,
+                "timestamp": 1700000000000u64, "triggerCondition": "Price below 2900",
+                "isTrigger": true, "triggerPx": "2900.0", "children": [],
+                "isPositionTpsl": true, "reduceOnly": true, "orderType": "Stop Market",
+                "origSz": "0.0", "tif": null, "cloid": null
+            },
+            {
+                "coin": "ETH", "side": "B", "limitPx": "2800.0", "sz": "0.5", "oid": 3,
+                "timestamp": 1700000000000u64, "triggerCondition": "N/A",
+                "isTrigger": false, "triggerPx": "0.0", "children": [],
+                "isPositionTpsl": false, "reduceOnly": false, "orderType": "Limit",
+                "origSz": "0.5", "tif": "Gtc", "cloid": null
+            }
+        ]))
+        .unwrap();
+
+        let matching = |asset: &str, tpsl: Option<&str>| -> Vec<u64> {
+            orders
+                .iter()
+                .filter(|order| is_position_tpsl(order, asset, tpsl))
+                .map(|order| order.oid)
+                .collect()
+        };
+        assert_eq!(matching("ETH", None), vec![1, 2]);
+        assert_eq!(matching("ETH", Some("sl")), vec![2]);
+        assert!(matching("BTC", None).is_empty());
+        assert_eq!(orders[2].tpsl(), None);
+
+        let coin_to_asset = HashMap::from([("ETH".to_string(), 1)]);
+        let order = position_tpsl_order("ETH", false, 3300.0, "tp")
+            .convert(&coin_to_asset)
+            .unwrap();
+        let order = serde_json::to_value(order).unwrap();
+        assert_eq!(order["s"], "0");
+        assert_eq!(order["r"], true);
+        assert_eq!(order["t"]["trigger"]["tpsl"], "tp");
+    }
+
+    fn assert_send<T: Send>(_: T) {}
+
+    #[test]
+    fn test_shared_client_is_refreshable() -> Result<()> {
+        let snapshot: MetaSnapshot = serde_json::from_value(serde_json::json!({
+            "perpMeta": [{
+                "dex": null,
+                "meta": {"universe": [], "marginTables": [], "collateralToken": 0},
+            }],
+            "spotMeta": {"universe": [], "tokens": []},
+        }))
+        .unwrap();
+        let client = Arc::new(ExchangeClient::from_snapshot(
+            None,
+            get_wallet()?,
+            Some(BaseUrl::Localhost),
+            snapshot,
+            None,
+        )?);
+        // The meta lock is never held across an await, so the futures can be spawned
+        assert_send(client.refresh_meta());
+        assert_send(client.market_open(MarketOrderParams {
+            asset: "ETH",
+            is_buy: true,
+            sz: 1.0,
+            px: None,
+            slippage: None,
+            cloid: None,
+            wallet: None,
+        }));
+        assert_send(client.cancel_all(&AssetFilter::All, None));
+        assert_send(client.flatten_all(&AssetFilter::All, None, None));
+        Ok(())
+    }
+
+    #[test]
+    fn test_hip3_perp_dex() -> Result<()> {
+        let asset = |name: &str| serde_json::json!({"name": name, "szDecimals": 2, "maxLeverage": 10, "marginTableId": 10});
+        let snapshot: MetaSnapshot = serde_json::from_value(serde_json::json!({
+            "perpMeta": [
+                {
+                    "dex": null,
+                    "meta": {"universe": [asset("ETH")], "marginTables": [], "collateralToken": 0},
+                },
+                {
+                    "dex": {"name": "test", "id": 1},
+                    "meta": {"universe": [asset("test:ABC")], "marginTables": [], "collateralToken": 0},
+                },
+            ],
+            "spotMeta": {"universe": [], "tokens": []},
+        }))
+        .unwrap();
+        let client = ExchangeClient::from_snapshot(
+            None,
+            get_wallet()?,
+            Some(BaseUrl::Localhost),
+            snapshot,
+            None,
+        )?;
+        // Info requests go to the same API as the exchange requests
+        assert_eq!(
+            client.info_client.http_client.base_url,
+            crate::LOCAL_API_URL
+        );
+
+        assert_eq!(client.meta().coin_to_asset.get("test:ABC"), Some(&110000));
+        assert_eq!(client.perp_dex("ETH"), None);
+        assert_eq!(client.perp_dex("test:ABC"), Some("test".to_string()));
+        assert_eq!(client.perp_dexs(), vec![None, Some("test".to_string())]);
+
+        let order = ClientOrderRequest {
+            asset: "test:ABC".to_string(),
+            is_buy: true,
+            reduce_only: false,
+            limit_px: 10.0,
+            sz: 1.0,
+            cloid: None,
+            order_type: ClientOrder::Limit(ClientLimit {
+                tif: "Gtc".to_string(),
+            }),
+        };
+        let action =
+            client.with_actions(|actions| actions.order(vec![order], Grouping::Na.into(), None))?;
+        let action = serde_json::to_value(action).unwrap();
+        assert_eq!(action["orders"][0]["a"], 110000);
+        Ok(())
+    }
 }
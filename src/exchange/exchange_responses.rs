@@ -1,4 +1,8 @@
 use serde::Deserialize;
+use thiserror::Error as ThisError;
+use uuid::Uuid;
+
+use crate::{prelude::*, ClientCancelRequest, ClientCancelRequestCloid, ClientOrderRequest, Error};
 
 #[derive(Deserialize, Debug, Clone)]
 pub struct RestingOrder {
@@ -13,6 +17,12 @@ pub struct FilledOrder {
     pub oid: u64,
 }
 
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TwapRunning {
+    pub twap_id: u64,
+}
+
 #[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub enum ExchangeDataStatus {
@@ -22,11 +32,16 @@ pub enum ExchangeDataStatus {
     Error(String),
     Resting(RestingOrder),
     Filled(FilledOrder),
+    /// A TWAP order was accepted
+    Running(TwapRunning),
 }
 
 #[derive(Deserialize, Debug, Clone)]
 pub struct ExchangeDataStatuses {
+    #[serde(default)]
     pub statuses: Vec<ExchangeDataStatus>,
+    /// Set instead of `statuses` by actions on a single TWAP
+    pub status: Option<ExchangeDataStatus>,
 }
 
 #[derive(Deserialize, Debug, Clone)]
@@ -36,10 +51,303 @@ pub struct ExchangeResponse {
     pub data: Option<ExchangeDataStatuses>,
 }
 
+/// Response of actions returning a single value, like the address of a created sub-account
+#[derive(Deserialize, Debug, Clone)]
+pub struct TypedExchangeResponse<T> {
+    #[serde(rename = "type")]
+    pub response_type: String,
+    pub data: T,
+}
+
 #[derive(Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 #[serde(tag = "status", content = "response")]
-pub enum ExchangeResponseStatus {
-    Ok(ExchangeResponse),
+pub enum ExchangeResponseStatus<T = ExchangeResponse> {
+    Ok(T),
     Err(String),
 }
+
+impl<T> ExchangeResponseStatus<T> {
+    /// The response, or the rejection of the whole action as `Error::Exchange`
+    pub fn into_result(self) -> Result<T> {
+        match self {
+            ExchangeResponseStatus::Ok(response) => Ok(response),
+            ExchangeResponseStatus::Err(e) => Err(Error::Exchange(e.into())),
+        }
+    }
+}
+
+/// Response of actions that only acknowledge success, such as transfers
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum DefaultResponse {
+    Default,
+}
+
+/// Rejection reported by the exchange, for a whole action or for one order or cancel of it.
+/// Each variant keeps the exchange's message.
+#[derive(ThisError, Debug, Clone, PartialEq)]
+pub enum ExchangeError {
+    #[error("Insufficient margin: {0}")]
+    InsufficientMargin(String),
+    #[error("Price not divisible by tick size: {0}")]
+    BadTickSize(String),
+    #[error("Order below minimum value: {0}")]
+    MinNotional(String),
+    #[error("Reduce-only order would increase position: {0}")]
+    ReduceOnly(String),
+    #[error("Unknown order: {0}")]
+    UnknownOid(String),
+    #[error("{0}")]
+    Other(String),
+}
+
+impl ExchangeError {
+    pub fn message(&self) -> &str {
+        match self {
+            ExchangeError::InsufficientMargin(message)
+            | ExchangeError::BadTickSize(message)
+            | ExchangeError::MinNotional(message)
+            | ExchangeError::ReduceOnly(message)
+            | ExchangeError::UnknownOid(message)
+            | ExchangeError::Other(message) => message,
+        }
+    }
+}
+
+impl From<String> for ExchangeError {
+    fn from(message: String) -> Self {
+        let lowercase = message.to_lowercase();
+        let matches = |patterns: &[&str]| patterns.iter().any(|p| lowercase.contains(p));
+        if matches(&["insufficient margin", "insufficient spot balance"]) {
+            ExchangeError::InsufficientMargin(message)
+        } else if matches(&["tick size"]) {
+            ExchangeError::BadTickSize(message)
+        } else if matches(&["minimum value"]) {
+            ExchangeError::MinNotional(message)
+        } else if matches(&["reduce only"]) {
+            ExchangeError::ReduceOnly(message)
+        } else if matches(&["never placed", "already canceled", "unknown oid"]) {
+            ExchangeError::UnknownOid(message)
+        } else {
+            ExchangeError::Other(message)
+        }
+    }
+}
+
+/// Outcome of one order of a bulk order, with the request it answers
+#[derive(Debug, Clone)]
+pub struct OrderResult {
+    pub request: ClientOrderRequest,
+    pub cloid: Option<Uuid>,
+    pub status: std::result::Result<ExchangeDataStatus, ExchangeError>,
+}
+
+impl OrderResult {
+    /// Id of the resting or filled order
+    pub fn oid(&self) -> Option<u64> {
+        match &self.status {
+            Ok(ExchangeDataStatus::Resting(order)) => Some(order.oid),
+            Ok(ExchangeDataStatus::Filled(order)) => Some(order.oid),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CancelResult {
+    pub asset: String,
+    pub oid: u64,
+    pub result: std::result::Result<(), ExchangeError>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CancelByCloidResult {
+    pub asset: String,
+    pub cloid: Uuid,
+    pub result: std::result::Result<(), ExchangeError>,
+}
+
+fn status_result(
+    status: ExchangeDataStatus,
+) -> std::result::Result<ExchangeDataStatus, ExchangeError> {
+    match status {
+        ExchangeDataStatus::Error(e) => Err(e.into()),
+        status => Ok(status),
+    }
+}
+
+impl ExchangeResponse {
+    fn statuses(self, expected: usize) -> Result<Vec<ExchangeDataStatus>> {
+        let statuses = self.data.map(|data| data.statuses).unwrap_or_default();
+        if statuses.len() != expected {
+            return Err(Error::GenericParse(format!(
+                "Expected {expected} statuses in the response, got {}",
+                statuses.len()
+            )));
+        }
+        Ok(statuses)
+    }
+
+    /// Matches the statuses of an order response to the orders it was sent for
+    pub fn order_results(self, orders: Vec<ClientOrderRequest>) -> Result<Vec<OrderResult>> {
+        let statuses = self.statuses(orders.len())?;
+        Ok(orders
+            .into_iter()
+            .zip(statuses)
+            .map(|(request, status)| OrderResult {
+                cloid: request.cloid,
+                request,
+                status: status_result(status),
+            })
+            .collect())
+    }
+
+    /// Matches the statuses of a cancel response to the cancels it was sent for
+    pub fn cancel_results(self, cancels: Vec<ClientCancelRequest>) -> Result<Vec<CancelResult>> {
+        let statuses = self.statuses(cancels.len())?;
+        Ok(cancels
+            .into_iter()
+            .zip(statuses)
+            .map(|(cancel, status)| CancelResult {
+                asset: cancel.asset,
+                oid: cancel.oid,
+                result: status_result(status).map(|_| ()),
+            })
+            .collect())
+    }
+
+    /// Matches the statuses of a cancel by cloid response to the cancels it was sent for
+    pub fn cancel_by_cloid_results(
+        self,
+        cancels: Vec<ClientCancelRequestCloid>,
+    ) -> Result<Vec<CancelByCloidResult>> {
+        let statuses = self.statuses(cancels.len())?;
+        Ok(cancels
+            .into_iter()
+            .zip(statuses)
+            .map(|(cancel, status)| CancelByCloidResult {
+                asset: cancel.asset,
+                cloid: cancel.cloid,
+                result: status_result(status).map(|_| ()),
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ClientLimit, ClientOrder};
+
+    fn order(cloid: Option<Uuid>) -> ClientOrderRequest {
+        ClientOrderRequest {
+            asset: "ETH".to_string(),
+            is_buy: true,
+            reduce_only: false,
+            limit_px: 1800.0,
+            sz: 0.01,
+            cloid,
+            order_type: ClientOrder::Limit(ClientLimit {
+                tif: "Gtc".to_string(),
+            }),
+        }
+    }
+
+    #[test]
+    fn test_order_results() -> Result<()> {
+        let response: ExchangeResponseStatus = serde_json::from_str(
+            r#"{"status":"ok","response":{"type":"order","data":{"statuses":[
+                {"resting":{"oid":77738308}},
+                {"error":"Order must have minimum value of $10. asset=4"}
+            ]}}}"#,
+        )
+        .map_err(|e| Error::JsonParse(e.to_string()))?;
+        let cloid = Uuid::new_v4();
+
+        let results = response
+            .into_result()?
+            .order_results(vec![order(Some(cloid)), order(None)])?;
+        assert_eq!(results[0].cloid, Some(cloid));
+        assert_eq!(results[0].oid(), Some(77738308));
+        assert_eq!(results[1].oid(), None);
+        assert!(matches!(
+            results[1].status,
+            Err(ExchangeError::MinNotional(_))
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_cancel_results() -> Result<()> {
+        let response: ExchangeResponseStatus = serde_json::from_str(
+            r#"{"status":"ok","response":{"type":"cancel","data":{"statuses":[
+                "success",
+                {"error":"Order was never placed, already canceled, or filled. asset=4"}
+            ]}}}"#,
+        )
+        .map_err(|e| Error::JsonParse(e.to_string()))?;
+        let cancels = vec![
+            ClientCancelRequest {
+                asset: "ETH".to_string(),
+                oid: 1,
+            },
+            ClientCancelRequest {
+                asset: "ETH".to_string(),
+                oid: 2,
+            },
+        ];
+
+        let results = response.into_result()?.cancel_results(cancels)?;
+        assert_eq!(results[0].oid, 1);
+        assert_eq!(results[0].result, Ok(()));
+        assert_eq!(results[1].oid, 2);
+        assert!(matches!(
+            results[1].result,
+            Err(ExchangeError::UnknownOid(_))
+        ));
+
+        // Statuses that cannot be matched to the requests are an error
+        let response: ExchangeResponseStatus = serde_json::from_str(
+            r#"{"status":"ok","response":{"type":"cancel","data":{"statuses":["success"]}}}"#,
+        )
+        .map_err(|e| Error::JsonParse(e.to_string()))?;
+        assert!(response.into_result()?.cancel_results(vec![]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_exchange_errors() -> Result<()> {
+        let response: ExchangeResponseStatus<DefaultResponse> =
+            serde_json::from_str(r#"{"status":"ok","response":{"type":"default"}}"#)
+                .map_err(|e| Error::JsonParse(e.to_string()))?;
+        assert_eq!(response.into_result()?, DefaultResponse::Default);
+
+        let response: ExchangeResponseStatus<DefaultResponse> = serde_json::from_str(
+            r#"{"status":"err","response":"Insufficient margin to place order. asset=4"}"#,
+        )
+        .map_err(|e| Error::JsonParse(e.to_string()))?;
+        assert!(matches!(
+            response.into_result(),
+            Err(Error::Exchange(ExchangeError::InsufficientMargin(_)))
+        ));
+
+        for (message, expected) in [
+            (
+                "Price must be divisible by tick size. asset=4",
+                ExchangeError::BadTickSize as fn(String) -> ExchangeError,
+            ),
+            (
+                "Reduce only order would increase position. asset=4",
+                ExchangeError::ReduceOnly,
+            ),
+            ("User or API Wallet does not exist.", ExchangeError::Other),
+        ] {
+            assert_eq!(
+                ExchangeError::from(message.to_string()),
+                expected(message.to_string())
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,162 @@
+use super::exchange_client::round_to_significant_and_decimal;
+use crate::{
+    CancelResult, ClientLimit, ClientOrder, ClientOrderRequest, Error, ExchangeError, OrderResult,
+};
+
+/// Cancels and closes sent per request by `cancel_all` and `flatten_all`
+pub(crate) const KILL_SWITCH_BATCH_SIZE: usize = 50;
+
+/// Spot asset ids start at 10000, HIP-3 perp dexs at 100000
+pub(crate) fn is_spot_asset(asset: u32) -> bool {
+    (10_000..100_000).contains(&asset)
+}
+
+/// Which orders or positions `ExchangeClient::cancel_all` and `flatten_all` act on
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum AssetFilter {
+    #[default]
+    All,
+    Perps,
+    Spot,
+    /// Perps by name and spot by pair ("PURR/USDC" or "@107") or token name
+    Coins(Vec<String>),
+}
+
+impl AssetFilter {
+    pub fn includes_perps(&self) -> bool {
+        !matches!(self, AssetFilter::Spot)
+    }
+
+    pub fn includes_spot(&self) -> bool {
+        !matches!(self, AssetFilter::Perps)
+    }
+
+    /// Whether an asset known by any of `names` passes the filter
+    pub fn matches(&self, is_spot: bool, names: &[&str]) -> bool {
+        match self {
+            AssetFilter::All => true,
+            AssetFilter::Perps => !is_spot,
+            AssetFilter::Spot => is_spot,
+            AssetFilter::Coins(coins) => coins.iter().any(|coin| names.contains(&coin.as_str())),
+        }
+    }
+}
+
+/// Outcome of `ExchangeClient::cancel_all`, one result per open order that matched the filter
+#[derive(Debug, Clone, Default)]
+pub struct CancelAllReport {
+    pub results: Vec<CancelResult>,
+}
+
+impl CancelAllReport {
+    pub fn cancelled(&self) -> impl Iterator<Item = &CancelResult> {
+        self.results.iter().filter(|result| result.result.is_ok())
+    }
+
+    pub fn failed(&self) -> impl Iterator<Item = &CancelResult> {
+        self.results.iter().filter(|result| result.result.is_err())
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.failed().next().is_none()
+    }
+}
+
+/// Outcome of `ExchangeClient::flatten_all`
+#[derive(Debug, Clone, Default)]
+pub struct FlattenReport {
+    /// One closing order per position or spot balance
+    pub results: Vec<OrderResult>,
+    /// Coins that could not be closed before sending anything, with the reason
+    pub skipped: Vec<(String, String)>,
+}
+
+impl FlattenReport {
+    pub fn closed(&self) -> impl Iterator<Item = &OrderResult> {
+        self.results.iter().filter(|result| result.status.is_ok())
+    }
+
+    pub fn failed(&self) -> impl Iterator<Item = &OrderResult> {
+        self.results.iter().filter(|result| result.status.is_err())
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.skipped.is_empty() && self.failed().next().is_none()
+    }
+}
+
+/// Reported for each cancel or close of a batch the exchange rejected as a whole
+pub(crate) fn batch_error(error: Error) -> ExchangeError {
+    match error {
+        Error::Exchange(e) => e,
+        e => ExchangeError::Other(e.to_string()),
+    }
+}
+
+/// An Ioc order selling `sz` if `is_buy` is false, priced `slippage` through the mid
+pub(crate) fn close_order(
+    coin: &str,
+    is_buy: bool,
+    sz: f64,
+    mid: f64,
+    slippage: f64,
+    price_decimals: u32,
+    reduce_only: bool,
+) -> ClientOrderRequest {
+    let px = if is_buy {
+        mid * (1.0 + slippage)
+    } else {
+        mid * (1.0 - slippage)
+    };
+    ClientOrderRequest {
+        asset: coin.to_string(),
+        is_buy,
+        reduce_only,
+        limit_px: round_to_significant_and_decimal(px, 5, price_decimals),
+        sz,
+        cloid: None,
+        order_type: ClientOrder::Limit(ClientLimit {
+            tif: "Ioc".to_string(),
+        }),
+    }
+}
+
+/// Rounds down so a spot balance is never oversold
+pub(crate) fn floor_to_decimals(value: f64, decimals: u32) -> f64 {
+    let factor = 10f64.powi(decimals as i32);
+    (value * factor).floor() / factor
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_asset_filter() {
+        assert!(AssetFilter::All.matches(true, &["PURR/USDC"]));
+        assert!(!AssetFilter::Perps.matches(true, &["PURR/USDC"]));
+        assert!(AssetFilter::Perps.matches(false, &["ETH"]));
+        assert!(!AssetFilter::Spot.includes_perps());
+
+        let coins = AssetFilter::Coins(vec!["ETH".to_string(), "HYPE".to_string()]);
+        assert!(coins.matches(false, &["ETH"]));
+        assert!(coins.matches(true, &["@107", "HYPE/USDC", "HYPE"]));
+        assert!(!coins.matches(false, &["BTC"]));
+
+        assert!(is_spot_asset(10_107));
+        assert!(!is_spot_asset(4));
+        assert!(!is_spot_asset(110_000));
+    }
+
+    #[test]
+    fn test_close_order() {
+        let order = close_order("ETH", false, 1.5, 3000.0, 0.05, 2, true);
+        assert_eq!(order.limit_px, 2850.0);
+        assert!(order.reduce_only && !order.is_buy);
+
+        let order = close_order("@107", true, 3.0, 12.3456, 0.1, 6, false);
+        assert_eq!(order.limit_px, 13.58);
+
+        assert_eq!(floor_to_decimals(1.23999, 2), 1.23);
+    }
+}
@@ -1,18 +1,31 @@
+mod action_builder;
 mod actions;
+mod agent_manager;
+mod bracket;
 mod builder;
 mod cancel;
+mod dead_mans_switch;
 mod exchange_client;
 mod exchange_responses;
+mod kill_switch;
 mod modify;
 mod order;
+mod twap;
 
+pub(crate) use action_builder::ActionBuilder;
 pub use actions::*;
+pub use agent_manager::{AgentKeystore, AgentManager, ManagedAgent};
+pub use bracket::{BracketOrder, BracketOrderResult, BracketPrice};
 pub use builder::*;
 pub use cancel::{CancelRequest, ClientCancelRequest, ClientCancelRequestCloid};
+pub use dead_mans_switch::{DeadMansSwitch, ScheduleCancelConfig, MIN_SCHEDULE_CANCEL_DELAY};
 pub use exchange_client::*;
 pub use exchange_responses::*;
+pub use kill_switch::{AssetFilter, CancelAllReport, FlattenReport};
 pub use modify::{ClientModifyRequest, ModifyRequest};
+pub(crate) use order::OrderRequest;
 pub use order::{
-    ClientLimit, ClientOrder, ClientOrderRequest, ClientTrigger, MarketCloseParams,
+    ClientLimit, ClientOrder, ClientOrderRequest, ClientTrigger, Grouping, MarketCloseParams,
     MarketOrderParams, Order,
 };
+pub use twap::{ClientTwapCancelRequest, ClientTwapRequest, TwapRequest};
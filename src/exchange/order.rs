@@ -28,6 +28,35 @@ pub enum Order {
     Trigger(Trigger),
 }
 
+/// How the orders of a `BulkOrder` relate to each other
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum Grouping {
+    /// Independent orders
+    #[default]
+    Na,
+    /// An entry order followed by TP/SL triggers sized to that order
+    NormalTpsl,
+    /// TP/SL triggers attached to the whole position, resizing with it
+    PositionTpsl,
+}
+
+impl Grouping {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Grouping::Na => "na",
+            Grouping::NormalTpsl => "normalTpsl",
+            Grouping::PositionTpsl => "positionTpsl",
+        }
+    }
+}
+
+impl From<Grouping> for String {
+    fn from(grouping: Grouping) -> String {
+        grouping.as_str().to_string()
+    }
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct OrderRequest {
@@ -60,24 +89,24 @@ pub struct ClientTrigger {
 }
 
 #[derive(Debug)]
-pub struct MarketOrderParams<'a> {
+pub struct MarketOrderParams<'a, S = LocalWallet> {
     pub asset: &'a str,
     pub is_buy: bool,
     pub sz: f64,
     pub px: Option<f64>,
     pub slippage: Option<f64>,
     pub cloid: Option<Uuid>,
-    pub wallet: Option<&'a LocalWallet>,
+    pub wallet: Option<&'a S>,
 }
 
 #[derive(Debug)]
-pub struct MarketCloseParams<'a> {
+pub struct MarketCloseParams<'a, S = LocalWallet> {
     pub asset: &'a str,
     pub sz: Option<f64>,
     pub px: Option<f64>,
     pub slippage: Option<f64>,
     pub cloid: Option<Uuid>,
-    pub wallet: Option<&'a LocalWallet>,
+    pub wallet: Option<&'a S>,
 }
 
 #[derive(Debug, Clone)]
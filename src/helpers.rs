@@ -1,30 +1,7 @@
 use crate::{consts::*, prelude::*, Error};
-use chrono::prelude::Utc;
-use lazy_static::lazy_static;
-use log::info;
 use rand::{thread_rng, Rng};
-use std::sync::atomic::{AtomicU64, Ordering};
 use uuid::Uuid;
 
-fn now_timestamp_ms() -> u64 {
-    let now = Utc::now();
-    now.timestamp_millis() as u64
-}
-
-pub(crate) fn next_nonce() -> u64 {
-    let nonce = CUR_NONCE.fetch_add(1, Ordering::Relaxed);
-    let now_ms = now_timestamp_ms();
-    if nonce > now_ms + 1000 {
-        info!("nonce progressed too far ahead {nonce} {now_ms}");
-    }
-    // more than 300 seconds behind
-    if nonce + 300000 < now_ms {
-        CUR_NONCE.fetch_max(now_ms + 1, Ordering::Relaxed);
-        return now_ms;
-    }
-    nonce
-}
-
 pub(crate) const WIRE_DECIMALS: u8 = 8;
 
 pub(crate) fn float_to_string_for_hashing(x: f64) -> String {
@@ -94,10 +71,6 @@ impl BaseUrl {
     }
 }
 
-lazy_static! {
-    static ref CUR_NONCE: AtomicU64 = AtomicU64::new(now_timestamp_ms());
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -7,8 +7,10 @@ use crate::{
     prelude::*,
     req::HttpClient,
     ws::{Subscription, WsManager},
-    BaseUrl, Error, Message, OrderStatusResponse, ReferralResponse, UserFeesResponse,
-    UserFundingResponse, UserTokenBalanceResponse,
+    BaseUrl, DelegationResponse, DelegatorRewardResponse, DelegatorSummaryResponse, Error,
+    ExtraAgentsResponse, Message, MultiSigSigners, OrderStatusResponse, ReferralResponse,
+    SubAccountResponse, UserFeesResponse, UserFundingResponse, UserTokenBalanceResponse,
+    ValidatorSummaryResponse, VaultDetailsResponse,
 };
 
 use ethers::types::H160;
@@ -34,6 +36,8 @@ pub enum InfoRequest {
     #[serde(rename = "clearinghouseState")]
     UserState {
         user: H160,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        dex: Option<String>,
     },
     #[serde(rename = "batchClearinghouseStates")]
     UserStates {
@@ -48,6 +52,13 @@ pub enum InfoRequest {
     },
     OpenOrders {
         user: H160,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        dex: Option<String>,
+    },
+    FrontendOpenOrders {
+        user: H160,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        dex: Option<String>,
     },
     OrderStatus {
         user: H160,
@@ -96,15 +107,40 @@ pub enum InfoRequest {
         user: H160,
     },
     PerpDexs,
+    ExtraAgents {
+        user: H160,
+    },
+    UserToMultiSigSigners {
+        user: H160,
+    },
+    SubAccounts {
+        user: H160,
+    },
+    Delegations {
+        user: H160,
+    },
+    DelegatorSummary {
+        user: H160,
+    },
+    DelegatorRewards {
+        user: H160,
+    },
+    ValidatorSummaries,
+    #[serde(rename_all = "camelCase")]
+    VaultDetails {
+        vault_address: H160,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        user: Option<H160>,
+    },
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct PerpMeta {
     pub dex: Option<PerpDex>,
     pub meta: Meta,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct PerpDex {
     pub name: String,
     pub id: u32,
@@ -134,6 +170,18 @@ impl InfoClient {
         Self::new_internal(client, base_url, true).await
     }
 
+    /// An info client on the same connection pool and API as `http_client`
+    pub(crate) fn from_http_client(http_client: &HttpClient) -> InfoClient {
+        InfoClient {
+            http_client: HttpClient {
+                client: http_client.client.clone(),
+                base_url: http_client.base_url.clone(),
+            },
+            ws_manager: None,
+            reconnect: false,
+        }
+    }
+
     async fn new_internal(
         client: Option<Client>,
         base_url: Option<BaseUrl>,
@@ -201,16 +249,48 @@ impl InfoClient {
         serde_json::from_str(&return_data).map_err(|e| Error::JsonParse(e.to_string()))
     }
 
+    /// Open orders on the perp dex `dex` (and spot), the default dex if `None`
+    pub async fn dex_open_orders(
+        &self,
+        address: H160,
+        dex: Option<String>,
+    ) -> Result<Vec<OpenOrdersResponse>> {
+        let input = InfoRequest::OpenOrders { user: address, dex };
+        self.send_info_request(input).await
+    }
+
     pub async fn open_orders(&self, address: H160) -> Result<Vec<OpenOrdersResponse>> {
-        let input = InfoRequest::OpenOrders { user: address };
+        self.dex_open_orders(address, None).await
+    }
+
+    /// Open orders with their trigger conditions and TP/SL flags
+    pub async fn dex_frontend_open_orders(
+        &self,
+        address: H160,
+        dex: Option<String>,
+    ) -> Result<Vec<OpenOrdersResponse>> {
+        let input = InfoRequest::FrontendOpenOrders { user: address, dex };
         self.send_info_request(input).await
     }
 
-    pub async fn user_state(&self, address: H160) -> Result<UserStateResponse> {
-        let input = InfoRequest::UserState { user: address };
+    pub async fn frontend_open_orders(&self, address: H160) -> Result<Vec<OpenOrdersResponse>> {
+        self.dex_frontend_open_orders(address, None).await
+    }
+
+    /// Positions and margin on the perp dex `dex`, the default dex if `None`
+    pub async fn dex_user_state(
+        &self,
+        address: H160,
+        dex: Option<String>,
+    ) -> Result<UserStateResponse> {
+        let input = InfoRequest::UserState { user: address, dex };
         self.send_info_request(input).await
     }
 
+    pub async fn user_state(&self, address: H160) -> Result<UserStateResponse> {
+        self.dex_user_state(address, None).await
+    }
+
     pub async fn user_states(&self, addresses: Vec<H160>) -> Result<Vec<UserStateResponse>> {
         let input = InfoRequest::UserStates { users: addresses };
         self.send_info_request(input).await
@@ -382,6 +462,63 @@ impl InfoClient {
         self.send_info_request(input).await
     }
 
+    /// Agents the user has approved to sign L1 actions on their behalf
+    pub async fn extra_agents(&self, address: H160) -> Result<Vec<ExtraAgentsResponse>> {
+        let input = InfoRequest::ExtraAgents { user: address };
+        self.send_info_request(input).await
+    }
+
+    /// Signers of a multi-sig user, or None if the user is not multi-sig
+    pub async fn user_to_multi_sig_signers(
+        &self,
+        address: H160,
+    ) -> Result<Option<MultiSigSigners>> {
+        let input = InfoRequest::UserToMultiSigSigners { user: address };
+        self.send_info_request(input).await
+    }
+
+    /// Sub-accounts of the master account `address`, with their perp and spot balances
+    pub async fn sub_accounts(&self, address: H160) -> Result<Vec<SubAccountResponse>> {
+        let input = InfoRequest::SubAccounts { user: address };
+        let sub_accounts: Option<Vec<SubAccountResponse>> = self.send_info_request(input).await?;
+        Ok(sub_accounts.unwrap_or_default())
+    }
+
+    /// Details of a vault: followers, PnL history and leader commission. With `user`,
+    /// `follower_state` holds that user's position in the vault.
+    pub async fn vault_details(
+        &self,
+        vault_address: H160,
+        user: Option<H160>,
+    ) -> Result<VaultDetailsResponse> {
+        let input = InfoRequest::VaultDetails {
+            vault_address,
+            user,
+        };
+        self.send_info_request(input).await
+    }
+
+    /// HYPE the user has delegated, per validator
+    pub async fn delegations(&self, address: H160) -> Result<Vec<DelegationResponse>> {
+        let input = InfoRequest::Delegations { user: address };
+        self.send_info_request(input).await
+    }
+
+    pub async fn delegator_summary(&self, address: H160) -> Result<DelegatorSummaryResponse> {
+        let input = InfoRequest::DelegatorSummary { user: address };
+        self.send_info_request(input).await
+    }
+
+    pub async fn delegator_rewards(&self, address: H160) -> Result<Vec<DelegatorRewardResponse>> {
+        let input = InfoRequest::DelegatorRewards { user: address };
+        self.send_info_request(input).await
+    }
+
+    pub async fn validator_summaries(&self) -> Result<Vec<ValidatorSummaryResponse>> {
+        let input = InfoRequest::ValidatorSummaries;
+        self.send_info_request(input).await
+    }
+
     pub async fn perp_dexs(&self) -> Result<Vec<PerpDex>> {
         let input = InfoRequest::PerpDexs;
         let raw_dexs: Vec<Option<RawPerpDex>> = self.send_info_request(input).await?;
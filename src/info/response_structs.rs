@@ -1,7 +1,9 @@
 use crate::{
     info::{AssetPosition, Level, MarginSummary},
     DailyUserVlm, Delta, FeeSchedule, OrderInfo, Referrer, ReferrerState, UserTokenBalance,
+    ValidatorStats, VaultFollower, VaultPortfolio, VaultRelationship,
 };
+use ethers::types::H160;
 use serde::Deserialize;
 
 #[derive(Deserialize, Debug)]
@@ -38,6 +40,35 @@ pub struct OpenOrdersResponse {
     pub sz: String,
     pub timestamp: u64,
     pub cloid: Option<String>,
+    // The fields below are only returned by `InfoClient::frontend_open_orders`
+    /// e.g. "Limit", "Stop Market" or "Take Profit Limit"
+    pub order_type: Option<String>,
+    pub orig_sz: Option<String>,
+    pub tif: Option<String>,
+    #[serde(default)]
+    pub reduce_only: bool,
+    #[serde(default)]
+    pub is_trigger: bool,
+    pub trigger_px: Option<String>,
+    /// e.g. "Price above 3100", "N/A" for orders without a trigger
+    pub trigger_condition: Option<String>,
+    /// Whether the order is a TP/SL of the whole position rather than of a single order
+    #[serde(default)]
+    pub is_position_tpsl: bool,
+}
+
+impl OpenOrdersResponse {
+    /// "tp" or "sl" for take-profit and stop-loss triggers
+    pub fn tpsl(&self) -> Option<&'static str> {
+        let order_type = self.order_type.as_deref()?;
+        if order_type.starts_with("Take Profit") {
+            Some("tp")
+        } else if order_type.starts_with("Stop") {
+            Some("sl")
+        } else {
+            None
+        }
+    }
 }
 
 #[derive(serde::Deserialize, Debug)]
@@ -133,3 +164,91 @@ pub struct ReferralResponse {
     pub claimed_rewards: String,
     pub referrer_state: ReferrerState,
 }
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtraAgentsResponse {
+    pub name: String,
+    pub address: H160,
+    pub valid_until: u64,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SubAccountResponse {
+    pub name: String,
+    pub sub_account_user: H160,
+    pub master: H160,
+    pub clearinghouse_state: UserStateResponse,
+    pub spot_state: UserTokenBalanceResponse,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultDetailsResponse {
+    pub name: String,
+    pub vault_address: H160,
+    pub leader: H160,
+    pub description: String,
+    /// Account value and PnL history per period ("day", "week", "month", "allTime", ...)
+    pub portfolio: Vec<(String, VaultPortfolio)>,
+    pub apr: f64,
+    pub follower_state: Option<VaultFollower>,
+    /// Share of the vault owned by the leader
+    pub leader_fraction: f64,
+    /// Share of follower profits paid to the leader
+    pub leader_commission: f64,
+    pub followers: Vec<VaultFollower>,
+    pub max_distributable: f64,
+    pub max_withdrawable: f64,
+    pub is_closed: bool,
+    pub relationship: VaultRelationship,
+    pub allow_deposits: bool,
+    pub always_close_on_withdraw: bool,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DelegationResponse {
+    pub validator: H160,
+    /// HYPE
+    pub amount: String,
+    /// Undelegating is possible after this time
+    pub locked_until_timestamp: u64,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DelegatorSummaryResponse {
+    pub delegated: String,
+    pub undelegated: String,
+    pub total_pending_withdrawal: String,
+    pub n_pending_withdrawals: u64,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DelegatorRewardResponse {
+    pub time: u64,
+    /// "delegation" or "commission"
+    pub source: String,
+    pub total_amount: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidatorSummaryResponse {
+    pub validator: H160,
+    pub signer: H160,
+    pub name: String,
+    pub description: String,
+    pub n_recent_blocks: u64,
+    /// Total HYPE delegated, in wei (8 decimals)
+    pub stake: u64,
+    pub is_jailed: bool,
+    pub unjailable_after: Option<u64>,
+    pub is_active: bool,
+    pub commission: String,
+    /// Uptime and APR per period ("day", "week", "month")
+    pub stats: Vec<(String, ValidatorStats)>,
+}
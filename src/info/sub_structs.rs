@@ -164,3 +164,40 @@ pub struct ReferrerState {
 pub struct ReferrerData {
     pub required: String,
 }
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultPortfolio {
+    /// (timestamp, value) pairs
+    pub account_value_history: Vec<(u64, String)>,
+    pub pnl_history: Vec<(u64, String)>,
+    pub vlm: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultFollower {
+    pub user: H160,
+    pub vault_equity: String,
+    pub pnl: String,
+    pub all_time_pnl: String,
+    pub days_following: u64,
+    pub vault_entry_time: u64,
+    pub lockup_until: u64,
+}
+
+/// "normal", or "parent" / "child" for vaults grouped under another one
+#[derive(Deserialize, Debug, Clone)]
+pub struct VaultRelationship {
+    #[serde(rename = "type")]
+    pub relationship_type: String,
+    pub data: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidatorStats {
+    pub uptime_fraction: String,
+    pub predicted_apr: String,
+    pub n_samples: u64,
+}
@@ -6,6 +6,7 @@ mod helpers;
 pub mod info;
 mod market_maker;
 mod meta;
+mod nonce;
 pub mod prelude;
 mod proxy_digest;
 mod req;
@@ -18,6 +19,14 @@ pub use exchange::*;
 pub use helpers::{bps_diff, truncate_float, BaseUrl};
 pub use info::{info_client::*, *};
 pub use market_maker::{MarketMaker, MarketMakerInput, MarketMakerRestingOrder};
-pub use meta::{AssetMeta, Meta, SpotAssetMeta, SpotMeta};
-pub use unsigned::{bridge, UnsignedTransactionBuilder, UnsignedTransactionComponents};
+pub use meta::{AssetMeta, Meta, MetaSnapshot, SpotAssetMeta, SpotMeta};
+pub use nonce::{
+    check_nonce_window, Clock, LocalNonceManager, NonceManager, SystemClock, MAX_NONCE_AGE_MS,
+    MAX_NONCE_LEAD_MS, NONCE_SET_SIZE,
+};
+pub use signature::HyperliquidSigner;
+pub use unsigned::{
+    bridge, ActionDetail, MultiSigTransaction, OrderKind, OrderSummary, SigningSummary,
+    UnsignedTransactionBuilder, UnsignedTransactionComponents,
+};
 pub use ws::*;
@@ -1,15 +1,14 @@
-use ethers::{
-    signers::{LocalWallet, Signer},
-    types::H160,
-};
+use std::sync::Arc;
+
+use ethers::{signers::LocalWallet, types::H160};
 use log::{error, info};
 
 use tokio::sync::mpsc::unbounded_channel;
 
 use crate::{
-    bps_diff, truncate_float, BaseUrl, ClientCancelRequest, ClientLimit, ClientOrder,
-    ClientOrderRequest, ExchangeClient, ExchangeDataStatus, ExchangeResponseStatus, InfoClient,
-    Message, Subscription, UserData, EPSILON,
+    bps_diff, prelude::Result, truncate_float, BaseUrl, CancelResult, ClientCancelRequest,
+    ClientLimit, ClientOrder, ClientOrderRequest, DeadMansSwitch, ExchangeClient,
+    HyperliquidSigner, InfoClient, Message, ScheduleCancelConfig, Subscription, UserData, EPSILON,
 };
 #[derive(Debug)]
 pub struct MarketMakerRestingOrder {
@@ -19,18 +18,19 @@ pub struct MarketMakerRestingOrder {
 }
 
 #[derive(Debug)]
-pub struct MarketMakerInput {
+pub struct MarketMakerInput<S: HyperliquidSigner = LocalWallet> {
     pub asset: String,
     pub target_liquidity: f64, // Amount of liquidity on both sides to target
     pub half_spread: u16,      // Half of the spread for our market making (in BPS)
     pub max_bps_diff: u16, // Max deviation before we cancel and put new orders on the book (in BPS)
     pub max_absolute_position_size: f64, // Absolute value of the max position we can take on
     pub decimals: u32,     // Decimals to round to for pricing
-    pub wallet: LocalWallet, // Wallet containing private key
+    pub wallet: S,         // Signer for the trading account
+    pub schedule_cancel: Option<ScheduleCancelConfig>, // Cancel all orders if we stop running
 }
 
 #[derive(Debug)]
-pub struct MarketMaker {
+pub struct MarketMaker<S: HyperliquidSigner + 'static = LocalWallet> {
     pub asset: String,
     pub target_liquidity: f64,
     pub half_spread: u16,
@@ -42,21 +42,25 @@ pub struct MarketMaker {
     pub cur_position: f64,
     pub latest_mid_price: f64,
     pub info_client: InfoClient,
-    pub exchange_client: ExchangeClient,
+    pub exchange_client: Arc<ExchangeClient<S>>,
     pub user_address: H160,
+    pub schedule_cancel: Option<ScheduleCancelConfig>,
+    pub dead_mans_switch: Option<DeadMansSwitch<S>>,
 }
 
-impl MarketMaker {
-    pub async fn new(input: MarketMakerInput) -> MarketMaker {
+impl<S: HyperliquidSigner + 'static> MarketMaker<S> {
+    /// Fails if the clients cannot be set up or `schedule_cancel` is invalid
+    pub async fn new(input: MarketMakerInput<S>) -> Result<MarketMaker<S>> {
+        if let Some(config) = &input.schedule_cancel {
+            config.validate()?;
+        }
         let user_address = input.wallet.address();
 
-        let info_client = InfoClient::new(None, Some(BaseUrl::Testnet)).await.unwrap();
+        let info_client = InfoClient::new(None, Some(BaseUrl::Testnet)).await?;
         let exchange_client =
-            ExchangeClient::new(None, input.wallet, Some(BaseUrl::Testnet), None, None)
-                .await
-                .unwrap();
+            ExchangeClient::new(None, input.wallet, Some(BaseUrl::Testnet), None, None).await?;
 
-        MarketMaker {
+        Ok(MarketMaker {
             asset: input.asset,
             target_liquidity: input.target_liquidity,
             half_spread: input.half_spread,
@@ -76,14 +80,26 @@ impl MarketMaker {
             cur_position: 0.0,
             latest_mid_price: -1.0,
             info_client,
-            exchange_client,
+            exchange_client: Arc::new(exchange_client),
             user_address,
-        }
+            schedule_cancel: input.schedule_cancel,
+            dead_mans_switch: None,
+        })
     }
 
     pub async fn start(&mut self) {
         let (sender, mut receiver) = unbounded_channel();
 
+        // Have the exchange cancel our orders if we crash or stop processing messages
+        if let Some(config) = self.schedule_cancel {
+            match DeadMansSwitch::start(Arc::clone(&self.exchange_client), config) {
+                Ok(dead_mans_switch) => self.dead_mans_switch = Some(dead_mans_switch),
+                Err(e) => {
+                    error!("Could not start the dead man's switch, continuing without it: {e}")
+                }
+            }
+        }
+
         // Subscribe to UserEvents for fills
         self.info_client
             .subscribe(
@@ -103,6 +119,9 @@ impl MarketMaker {
 
         loop {
             let message = receiver.recv().await.unwrap();
+            if let Some(dead_mans_switch) = &self.dead_mans_switch {
+                dead_mans_switch.heartbeat();
+            }
             match message {
                 Message::AllMids(all_mids) => {
                     let all_mids = all_mids.data.mids;
@@ -153,32 +172,13 @@ impl MarketMaker {
     async fn attempt_cancel(&self, asset: String, oid: u64) -> bool {
         let cancel = self
             .exchange_client
-            .cancel(ClientCancelRequest { asset, oid }, None)
+            .bulk_cancel_results(vec![ClientCancelRequest { asset, oid }], None)
             .await;
 
-        match cancel {
-            Ok(cancel) => match cancel {
-                ExchangeResponseStatus::Ok(cancel) => {
-                    if let Some(cancel) = cancel.data {
-                        if !cancel.statuses.is_empty() {
-                            match cancel.statuses[0].clone() {
-                                ExchangeDataStatus::Success => {
-                                    return true;
-                                }
-                                ExchangeDataStatus::Error(e) => {
-                                    error!("Error with cancelling: {e}")
-                                }
-                                _ => unreachable!(),
-                            }
-                        } else {
-                            error!("Exchange data statuses is empty when cancelling: {cancel:?}")
-                        }
-                    } else {
-                        error!("Exchange response data is empty when cancelling: {cancel:?}")
-                    }
-                }
-                ExchangeResponseStatus::Err(e) => error!("Error with cancelling: {e}"),
-            },
+        match cancel.as_deref() {
+            Ok([CancelResult { result: Ok(()), .. }]) => return true,
+            Ok([CancelResult { result: Err(e), .. }]) => error!("Error with cancelling: {e}"),
+            Ok(results) => error!("Unexpected cancel results: {results:?}"),
             Err(e) => error!("Error with cancelling: {e}"),
         }
         false
@@ -193,7 +193,7 @@ impl MarketMaker {
     ) -> (f64, u64) {
         let order = self
             .exchange_client
-            .order(
+            .order_result(
                 ClientOrderRequest {
                     asset,
                     is_buy,
@@ -209,32 +209,10 @@ impl MarketMaker {
             )
             .await;
         match order {
-            Ok(order) => match order {
-                ExchangeResponseStatus::Ok(order) => {
-                    if let Some(order) = order.data {
-                        if !order.statuses.is_empty() {
-                            match order.statuses[0].clone() {
-                                ExchangeDataStatus::Filled(order) => {
-                                    return (amount, order.oid);
-                                }
-                                ExchangeDataStatus::Resting(order) => {
-                                    return (amount, order.oid);
-                                }
-                                ExchangeDataStatus::Error(e) => {
-                                    error!("Error with placing order: {e}")
-                                }
-                                _ => unreachable!(),
-                            }
-                        } else {
-                            error!("Exchange data statuses is empty when placing order: {order:?}")
-                        }
-                    } else {
-                        error!("Exchange response data is empty when placing order: {order:?}")
-                    }
-                }
-                ExchangeResponseStatus::Err(e) => {
-                    error!("Error with placing order: {e}")
-                }
+            Ok(order) => match (order.oid(), &order.status) {
+                (Some(oid), _) => return (amount, oid),
+                (None, Err(e)) => error!("Error with placing order: {e}"),
+                (None, Ok(status)) => error!("Unexpected status when placing order: {status:?}"),
             },
             Err(e) => error!("Error with placing order: {e}"),
         }
@@ -332,3 +310,34 @@ impl MarketMaker {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_new_rejects_invalid_schedule_cancel() {
+        let wallet: LocalWallet =
+            "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e"
+                .parse()
+                .unwrap();
+        let input = MarketMakerInput {
+            asset: "ETH".to_string(),
+            target_liquidity: 0.25,
+            half_spread: 1,
+            max_bps_diff: 2,
+            max_absolute_position_size: 0.5,
+            decimals: 1,
+            wallet,
+            schedule_cancel: Some(ScheduleCancelConfig {
+                window: Duration::from_secs(30),
+                rearm_interval: Duration::from_secs(30),
+                heartbeat_timeout: None,
+            }),
+        };
+        // Rejected before connecting to anything
+        assert!(MarketMaker::new(input).await.is_err());
+    }
+}
@@ -1,9 +1,11 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, fs, path::Path};
 
 use ethers::abi::ethereum_types::H128;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Deserialize, Debug, Clone)]
+use crate::{prelude::*, Error, InfoClient, PerpMeta};
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Meta {
     pub universe: Vec<AssetMeta>,
@@ -11,7 +13,7 @@ pub struct Meta {
     pub collateral_token: u32,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct SpotMeta {
     pub universe: Vec<SpotAssetMeta>,
     pub tokens: Vec<TokenInfo>,
@@ -48,6 +50,60 @@ impl SpotMeta {
     }
 }
 
+/// Everything needed to map coin names to asset ids without talking to the API.
+///
+/// Take one with `MetaSnapshot::fetch` on a connected machine, persist it with `save` and
+/// `load` it wherever the network is unavailable (air-gapped signers, offline tests).
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MetaSnapshot {
+    pub perp_meta: Vec<PerpMeta>,
+    pub spot_meta: SpotMeta,
+}
+
+impl MetaSnapshot {
+    pub async fn fetch(info_client: &InfoClient) -> Result<MetaSnapshot> {
+        Ok(MetaSnapshot {
+            perp_meta: info_client.global_meta().await?,
+            spot_meta: info_client.spot_meta().await?,
+        })
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let data = serde_json::to_string(self).map_err(|e| Error::JsonParse(e.to_string()))?;
+        fs::write(path, data).map_err(|e| Error::Io(e.to_string()))
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<MetaSnapshot> {
+        let data = fs::read_to_string(path).map_err(|e| Error::Io(e.to_string()))?;
+        serde_json::from_str(&data).map_err(|e| Error::JsonParse(e.to_string()))
+    }
+
+    /// Meta of the default perp dex, if the snapshot contains it
+    pub fn default_perp_meta(&self) -> Option<&Meta> {
+        self.perp_meta
+            .iter()
+            .find(|perp_meta| perp_meta.dex.is_none())
+            .map(|perp_meta| &perp_meta.meta)
+    }
+
+    pub fn coin_to_asset(&self) -> HashMap<String, u32> {
+        let mut coin_to_asset = HashMap::new();
+        for perp_meta in self.perp_meta.iter() {
+            // HIP-3 dexs live in their own asset id range
+            let hl_market_id_additive = if let Some(dex) = &perp_meta.dex {
+                100000 + dex.id * 10000
+            } else {
+                0
+            };
+            for (i, asset_meta) in perp_meta.meta.universe.iter().enumerate() {
+                coin_to_asset.insert(asset_meta.name.clone(), hl_market_id_additive + i as u32);
+            }
+        }
+        self.spot_meta.add_pair_and_name_to_index_map(coin_to_asset)
+    }
+}
+
 #[derive(Deserialize, Debug, Clone)]
 #[serde(untagged)]
 pub enum SpotMetaAndAssetCtxs {
@@ -66,7 +122,7 @@ pub struct SpotAssetContext {
     pub coin: String,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct AssetMeta {
     pub name: String,
@@ -77,21 +133,21 @@ pub struct AssetMeta {
     pub is_delisted: Option<bool>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct MarginTable {
     pub description: String,
     pub margin_tiers: Vec<MarginTier>,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct MarginTier {
     pub lower_bound: String,
     pub max_leverage: u32,
 }
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct SpotAssetMeta {
     pub tokens: [usize; 2],
@@ -100,7 +156,7 @@ pub struct SpotAssetMeta {
     pub is_canonical: bool,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct TokenInfo {
     pub name: String,
@@ -110,3 +166,74 @@ pub struct TokenInfo {
     pub token_id: H128,
     pub is_canonical: bool,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn asset(name: &str) -> serde_json::Value {
+        serde_json::json!({
+            "name": name,
+            "szDecimals": 2,
+            "maxLeverage": 10,
+            "marginTableId": 10,
+        })
+    }
+
+    fn snapshot() -> Result<MetaSnapshot> {
+        serde_json::from_value(serde_json::json!({
+            "perpMeta": [
+                {
+                    "dex": null,
+                    "meta": {
+                        "universe": [asset("BTC"), asset("ETH")],
+                        "marginTables": [],
+                        "collateralToken": 0,
+                    },
+                },
+                {
+                    "dex": {"name": "test", "id": 1},
+                    "meta": {
+                        "universe": [asset("test:ABC"), asset("test:XYZ")],
+                        "marginTables": [],
+                        "collateralToken": 0,
+                    },
+                },
+            ],
+            "spotMeta": {
+                "universe": [{"tokens": [1, 0], "name": "PURR/USDC", "index": 0, "isCanonical": true}],
+                "tokens": [
+                    {"name": "USDC", "szDecimals": 8, "weiDecimals": 8, "index": 0, "tokenId": "0x6d1e7cde53ba9467b783cb7c530ce054", "isCanonical": true},
+                    {"name": "PURR", "szDecimals": 0, "weiDecimals": 5, "index": 1, "tokenId": "0xc1fb593aeffbeb02f85e0308e9956a90", "isCanonical": true},
+                ],
+            },
+        }))
+        .map_err(|e| Error::JsonParse(e.to_string()))
+    }
+
+    #[test]
+    fn test_snapshot_coin_to_asset() -> Result<()> {
+        let coin_to_asset = snapshot()?.coin_to_asset();
+
+        assert_eq!(coin_to_asset["BTC"], 0);
+        assert_eq!(coin_to_asset["ETH"], 1);
+        assert_eq!(coin_to_asset["test:ABC"], 110000);
+        assert_eq!(coin_to_asset["test:XYZ"], 110001);
+        assert_eq!(coin_to_asset["PURR/USDC"], 10000);
+        Ok(())
+    }
+
+    #[test]
+    fn test_snapshot_save_and_load() -> Result<()> {
+        let snapshot = snapshot()?;
+        let path =
+            std::env::temp_dir().join(format!("meta_snapshot_{}.json", uuid::Uuid::new_v4()));
+
+        snapshot.save(&path)?;
+        let loaded = MetaSnapshot::load(&path);
+        fs::remove_file(&path).map_err(|e| Error::Io(e.to_string()))?;
+
+        assert_eq!(loaded?.coin_to_asset(), snapshot.coin_to_asset());
+        Ok(())
+    }
+}
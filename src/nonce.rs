@@ -0,0 +1,307 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use chrono::prelude::Utc;
+use ethers::types::H160;
+use lazy_static::lazy_static;
+
+use crate::{prelude::*, Error};
+
+/// The exchange keeps the highest 100 nonces of each signer. A new nonce must be larger
+/// than the smallest of them and not already in the set.
+pub const NONCE_SET_SIZE: usize = 100;
+/// Nonces older than this, relative to the block time, are rejected
+pub const MAX_NONCE_AGE_MS: u64 = 2 * 24 * 60 * 60 * 1000;
+/// Nonces further ahead than this, relative to the block time, are rejected
+pub const MAX_NONCE_LEAD_MS: u64 = 24 * 60 * 60 * 1000;
+
+/// Source of the current unix time in milliseconds
+pub trait Clock: Send + Sync {
+    fn now_ms(&self) -> u64;
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now_ms(&self) -> u64 {
+        Utc::now().timestamp_millis() as u64
+    }
+}
+
+impl<F: Fn() -> u64 + Send + Sync> Clock for F {
+    fn now_ms(&self) -> u64 {
+        self()
+    }
+}
+
+/// Hands out the nonces actions are signed with, keyed by the address that signs them
+pub trait NonceManager: Send + Sync + fmt::Debug {
+    fn next_nonce(&self, signer: H160) -> Result<u64>;
+
+    /// The time nonces are checked against before submitting
+    fn now_ms(&self) -> u64 {
+        SystemClock.now_ms()
+    }
+}
+
+/// Checks `nonce` is inside the window the exchange accepts around `now_ms`
+pub fn check_nonce_window(nonce: u64, now_ms: u64) -> Result<()> {
+    if nonce + MAX_NONCE_AGE_MS < now_ms {
+        return Err(Error::Nonce(format!(
+            "nonce {nonce} is more than two days older than {now_ms}"
+        )));
+    }
+    if nonce > now_ms + MAX_NONCE_LEAD_MS {
+        return Err(Error::Nonce(format!(
+            "nonce {nonce} is more than a day ahead of {now_ms}"
+        )));
+    }
+    Ok(())
+}
+
+/// Default `NonceManager`: the current time in milliseconds, bumped past the last nonce
+/// handed out to the same signer so nonces stay unique and increasing even within one
+/// millisecond or when the clock steps back.
+///
+/// Like the exchange it keeps the highest `NONCE_SET_SIZE` nonces of each signer, so
+/// `record_nonce` rejects nonces signed elsewhere that the exchange would reject too.
+///
+/// With a persistence file those nonces survive restarts. The file is not locked, so
+/// processes signing with the same key must not share one.
+pub struct LocalNonceManager {
+    clock: Box<dyn Clock>,
+    nonces: Mutex<HashMap<H160, BTreeSet<u64>>>,
+    path: Option<PathBuf>,
+}
+
+impl fmt::Debug for LocalNonceManager {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalNonceManager")
+            .field("nonces", &self.nonces)
+            .field("path", &self.path)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for LocalNonceManager {
+    fn default() -> Self {
+        Self::new(SystemClock)
+    }
+}
+
+impl LocalNonceManager {
+    pub fn new(clock: impl Clock + 'static) -> Self {
+        LocalNonceManager {
+            clock: Box::new(clock),
+            nonces: Mutex::new(HashMap::new()),
+            path: None,
+        }
+    }
+
+    /// Persists the nonces of each signer to `path`, resuming from it if it exists
+    pub fn with_persistence(mut self, path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if path.exists() {
+            let contents = std::fs::read_to_string(&path).map_err(|e| Error::Io(e.to_string()))?;
+            let nonces: HashMap<H160, BTreeSet<u64>> =
+                serde_json::from_str(&contents).map_err(|e| Error::JsonParse(e.to_string()))?;
+            self.nonces = Mutex::new(nonces);
+        }
+        self.path = Some(path);
+        Ok(self)
+    }
+
+    /// Records a nonce `signer` used outside this manager, e.g. from another process, so
+    /// later nonces are handed out past it. Fails if the exchange would reject it.
+    pub fn record_nonce(&self, signer: H160, nonce: u64) -> Result<()> {
+        let mut nonces = self
+            .nonces
+            .lock()
+            .map_err(|e| Error::Nonce(e.to_string()))?;
+        insert_nonce(nonces.entry(signer).or_default(), signer, nonce)?;
+        self.save(&nonces)
+    }
+
+    /// Writes a temporary file and renames it over `path`, so a crash mid-write never
+    /// leaves a truncated file behind
+    fn save(&self, nonces: &HashMap<H160, BTreeSet<u64>>) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let contents =
+            serde_json::to_string(nonces).map_err(|e| Error::JsonParse(e.to_string()))?;
+        let mut tmp_path = path.clone().into_os_string();
+        tmp_path.push(".tmp");
+        std::fs::write(&tmp_path, contents).map_err(|e| Error::Io(e.to_string()))?;
+        std::fs::rename(&tmp_path, path).map_err(|e| Error::Io(e.to_string()))
+    }
+}
+
+/// Adds `nonce` to the highest `NONCE_SET_SIZE` nonces of `signer`, applying the exchange's
+/// rules: it must be new and, once the set is full, larger than its smallest nonce
+fn insert_nonce(nonces: &mut BTreeSet<u64>, signer: H160, nonce: u64) -> Result<()> {
+    if nonces.contains(&nonce) {
+        return Err(Error::Nonce(format!(
+            "nonce {nonce} was already used by {signer:?}"
+        )));
+    }
+    if let Some(&lowest) = nonces.first() {
+        if nonces.len() >= NONCE_SET_SIZE && nonce <= lowest {
+            return Err(Error::Nonce(format!(
+                "nonce {nonce} is not above {lowest}, the lowest of the last {NONCE_SET_SIZE} nonces of {signer:?}"
+            )));
+        }
+    }
+    nonces.insert(nonce);
+    while nonces.len() > NONCE_SET_SIZE {
+        nonces.pop_first();
+    }
+    Ok(())
+}
+
+impl NonceManager for LocalNonceManager {
+    fn next_nonce(&self, signer: H160) -> Result<u64> {
+        let now_ms = self.clock.now_ms();
+        let mut nonces = self
+            .nonces
+            .lock()
+            .map_err(|e| Error::Nonce(e.to_string()))?;
+        let signer_nonces = nonces.entry(signer).or_default();
+
+        let nonce = match signer_nonces.last() {
+            Some(&last) if last >= now_ms => last + 1,
+            _ => now_ms,
+        };
+        // Running ahead of the clock means nonces are being used faster than one per
+        // millisecond for a long time, or the clock stepped far back since the last one
+        if nonce > now_ms + MAX_NONCE_LEAD_MS {
+            return Err(Error::Nonce(format!(
+                "next nonce {nonce} for {signer:?} is too far ahead of the clock {now_ms}"
+            )));
+        }
+
+        insert_nonce(signer_nonces, signer, nonce)?;
+        self.save(&nonces)?;
+        Ok(nonce)
+    }
+
+    fn now_ms(&self) -> u64 {
+        self.clock.now_ms()
+    }
+}
+
+lazy_static! {
+    static ref DEFAULT_NONCE_MANAGER: Arc<LocalNonceManager> =
+        Arc::new(LocalNonceManager::default());
+}
+
+/// Process-wide `LocalNonceManager` used by clients built without one, so clients signing
+/// with the same key never hand out the same nonce
+pub(crate) fn default_nonce_manager() -> Arc<dyn NonceManager> {
+    DEFAULT_NONCE_MANAGER.clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    fn manual_clock() -> (Arc<AtomicU64>, LocalNonceManager) {
+        let time = Arc::new(AtomicU64::new(1_700_000_000_000));
+        let clock_time = time.clone();
+        let manager = LocalNonceManager::new(move || clock_time.load(Ordering::SeqCst));
+        (time, manager)
+    }
+
+    #[test]
+    fn test_nonces_per_signer() {
+        let (time, manager) = manual_clock();
+        let alice = H160::from_low_u64_be(1);
+        let bob = H160::from_low_u64_be(2);
+
+        assert_eq!(manager.next_nonce(alice).unwrap(), 1_700_000_000_000);
+        assert_eq!(manager.next_nonce(alice).unwrap(), 1_700_000_000_001);
+        // Bob's sequence is independent
+        assert_eq!(manager.next_nonce(bob).unwrap(), 1_700_000_000_000);
+
+        time.store(1_700_000_005_000, Ordering::SeqCst);
+        assert_eq!(manager.next_nonce(alice).unwrap(), 1_700_000_005_000);
+
+        // The clock stepping back does not reuse nonces
+        time.store(1_700_000_000_000, Ordering::SeqCst);
+        assert_eq!(manager.next_nonce(alice).unwrap(), 1_700_000_005_001);
+    }
+
+    #[test]
+    fn test_nonce_too_far_ahead() {
+        let (time, manager) = manual_clock();
+        let signer = H160::from_low_u64_be(1);
+        manager.next_nonce(signer).unwrap();
+
+        time.fetch_sub(MAX_NONCE_LEAD_MS + 1, Ordering::SeqCst);
+        assert!(matches!(manager.next_nonce(signer), Err(Error::Nonce(_))));
+    }
+
+    #[test]
+    fn test_check_nonce_window() {
+        let now_ms = 1_700_000_000_000;
+        assert!(check_nonce_window(now_ms, now_ms).is_ok());
+        assert!(check_nonce_window(now_ms - MAX_NONCE_AGE_MS, now_ms).is_ok());
+        assert!(check_nonce_window(now_ms - MAX_NONCE_AGE_MS - 1, now_ms).is_err());
+        assert!(check_nonce_window(now_ms + MAX_NONCE_LEAD_MS + 1, now_ms).is_err());
+    }
+
+    #[test]
+    fn test_nonce_persistence() {
+        let path = std::env::temp_dir().join(format!(
+            "hl_nonces_{}.json",
+            Utc::now().timestamp_nanos_opt().unwrap_or_default()
+        ));
+        let signer = H160::from_low_u64_be(1);
+
+        let (_, manager) = manual_clock();
+        let manager = manager.with_persistence(&path).unwrap();
+        manager.next_nonce(signer).unwrap();
+        manager.next_nonce(signer).unwrap();
+
+        let (_, restarted) = manual_clock();
+        let restarted = restarted.with_persistence(&path).unwrap();
+        // Nonces used before the restart are still known
+        assert!(restarted.record_nonce(signer, 1_700_000_000_001).is_err());
+        assert_eq!(restarted.next_nonce(signer).unwrap(), 1_700_000_000_002);
+
+        let mut tmp_path = path.clone().into_os_string();
+        tmp_path.push(".tmp");
+        assert!(!Path::new(&tmp_path).exists());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_nonce_set() {
+        let (_, manager) = manual_clock();
+        let signer = H160::from_low_u64_be(1);
+
+        for nonce in 1_700_000_000_000..1_700_000_000_000 + NONCE_SET_SIZE as u64 {
+            manager.record_nonce(signer, nonce).unwrap();
+        }
+        // Duplicates and nonces at or below the lowest of the last 100 are rejected
+        assert!(manager.record_nonce(signer, 1_700_000_000_050).is_err());
+        assert!(manager.record_nonce(signer, 1_700_000_000_000).is_err());
+        assert!(manager.record_nonce(signer, 1_699_999_999_999).is_err());
+        assert_eq!(manager.next_nonce(signer).unwrap(), 1_700_000_000_100);
+        // The set only keeps the highest 100, so 1_700_000_000_001 is now the lowest
+        assert!(manager.record_nonce(signer, 1_700_000_000_001).is_err());
+        // Gaps above the lowest nonce can still be filled
+        manager.record_nonce(signer, 1_700_000_000_200).unwrap();
+        manager.record_nonce(signer, 1_700_000_000_150).unwrap();
+        // A fresh signer has no lower bound
+        manager
+            .record_nonce(H160::from_low_u64_be(2), 1_600_000_000_000)
+            .unwrap();
+    }
+}
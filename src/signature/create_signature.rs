@@ -1,13 +1,18 @@
 use ethers::{
     core::k256::{elliptic_curve::FieldBytes, Secp256k1},
     signers::LocalWallet,
-    types::{transaction::eip712::Eip712, Signature, H256, U256},
+    types::{transaction::eip712::Eip712, Signature, H160, H256, U256},
 };
 
-use crate::{prelude::*, proxy_digest::Sha256Proxy, signature::agent::l1, Error};
+use crate::{
+    prelude::*,
+    proxy_digest::Sha256Proxy,
+    signature::{agent::l1, HyperliquidSigner},
+    Error,
+};
 
-pub(crate) fn sign_l1_action(
-    wallet: &LocalWallet,
+pub(crate) async fn sign_l1_action<S: HyperliquidSigner>(
+    signer: &S,
     connection_id: H256,
     is_mainnet: bool,
 ) -> Result<Signature> {
@@ -17,19 +22,19 @@ pub(crate) fn sign_l1_action(
             source,
             connection_id,
         },
-        wallet,
+        signer,
     )
+    .await
 }
 
-pub(crate) fn sign_typed_data<T: Eip712>(payload: &T, wallet: &LocalWallet) -> Result<Signature> {
-    let encoded = payload
-        .encode_eip712()
-        .map_err(|e| Error::Eip712(e.to_string()))?;
-
-    sign_hash(H256::from(encoded), wallet)
+pub(crate) async fn sign_typed_data<T: Eip712 + Send + Sync, S: HyperliquidSigner>(
+    payload: &T,
+    signer: &S,
+) -> Result<Signature> {
+    signer.sign_typed_data(payload).await
 }
 
-fn sign_hash(hash: H256, wallet: &LocalWallet) -> Result<Signature> {
+pub(crate) fn sign_hash(hash: H256, wallet: &LocalWallet) -> Result<Signature> {
     let (sig, rec_id) = wallet
         .signer()
         .sign_digest_recoverable(Sha256Proxy::from(hash))
@@ -45,10 +50,19 @@ fn sign_hash(hash: H256, wallet: &LocalWallet) -> Result<Signature> {
     Ok(Signature { r, s, v })
 }
 
+/// Recovers the address that produced `signature` over an EIP-712 digest
+pub(crate) fn recover_signer(hash: H256, signature: &Signature) -> Result<H160> {
+    signature
+        .recover(hash)
+        .map_err(|e| Error::SignatureFailure(e.to_string()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{UsdSend, Withdraw3};
+    use crate::{
+        CDeposit, CWithdraw, SendAsset, TokenDelegate, UsdClassTransfer, UsdSend, Withdraw3,
+    };
     use std::str::FromStr;
 
     fn get_wallet() -> Result<LocalWallet> {
@@ -58,8 +72,8 @@ mod tests {
             .map_err(|e| Error::Wallet(e.to_string()))
     }
 
-    #[test]
-    fn test_sign_l1_action() -> Result<()> {
+    #[tokio::test]
+    async fn test_sign_l1_action() -> Result<()> {
         let wallet = get_wallet()?;
         let connection_id =
             H256::from_str("0xde6c4037798a4434ca03cd05f00e3b803126221375cd1e7eaaaf041768be06eb")
@@ -67,19 +81,49 @@ mod tests {
 
         let expected_mainnet_sig = "fa8a41f6a3fa728206df80801a83bcbfbab08649cd34d9c0bfba7c7b2f99340f53a00226604567b98a1492803190d65a201d6805e5831b7044f17fd530aec7841c";
         assert_eq!(
-            sign_l1_action(&wallet, connection_id, true)?.to_string(),
+            sign_l1_action(&wallet, connection_id, true)
+                .await?
+                .to_string(),
             expected_mainnet_sig
         );
         let expected_testnet_sig = "1713c0fc661b792a50e8ffdd59b637b1ed172d9a3aa4d801d9d88646710fb74b33959f4d075a7ccbec9f2374a6da21ffa4448d58d0413a0d335775f680a881431c";
         assert_eq!(
-            sign_l1_action(&wallet, connection_id, false)?.to_string(),
+            sign_l1_action(&wallet, connection_id, false)
+                .await?
+                .to_string(),
             expected_testnet_sig
         );
         Ok(())
     }
 
-    #[test]
-    fn test_sign_usd_transfer_action() -> Result<()> {
+    #[tokio::test]
+    async fn test_recover_signer() -> Result<()> {
+        let wallet = get_wallet()?;
+        let connection_id =
+            H256::from_str("0xde6c4037798a4434ca03cd05f00e3b803126221375cd1e7eaaaf041768be06eb")
+                .map_err(|e| Error::GenericParse(e.to_string()))?;
+
+        let signature = sign_l1_action(&wallet, connection_id, true).await?;
+        let digest = l1::Agent {
+            source: "a".to_string(),
+            connection_id,
+        }
+        .encode_eip712()
+        .map_err(|e| Error::Eip712(e.to_string()))?;
+
+        assert_eq!(
+            recover_signer(H256::from(digest), &signature)?,
+            HyperliquidSigner::address(&wallet)
+        );
+        assert_ne!(
+            recover_signer(connection_id, &signature)?,
+            HyperliquidSigner::address(&wallet)
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sign_usd_transfer_action() -> Result<()> {
         let wallet = get_wallet()?;
 
         let usd_send = UsdSend {
@@ -92,14 +136,14 @@ mod tests {
 
         let expected_sig = "214d507bbdaebba52fa60928f904a8b2df73673e3baba6133d66fe846c7ef70451e82453a6d8db124e7ed6e60fa00d4b7c46e4d96cb2bd61fd81b6e8953cc9d21b";
         assert_eq!(
-            sign_typed_data(&usd_send, &wallet)?.to_string(),
+            sign_typed_data(&usd_send, &wallet).await?.to_string(),
             expected_sig
         );
         Ok(())
     }
 
-    #[test]
-    fn test_sign_withdraw_from_bridge_action() -> Result<()> {
+    #[tokio::test]
+    async fn test_sign_withdraw_from_bridge_action() -> Result<()> {
         let wallet = get_wallet()?;
 
         let usd_send = Withdraw3 {
@@ -112,7 +156,121 @@ mod tests {
 
         let expected_sig = "b3172e33d2262dac2b4cb135ce3c167fda55dafa6c62213564ab728b9f9ba76b769a938e9f6d603dae7154c83bf5a4c3ebab81779dc2db25463a3ed663c82ae41c";
         assert_eq!(
-            sign_typed_data(&usd_send, &wallet)?.to_string(),
+            sign_typed_data(&usd_send, &wallet).await?.to_string(),
+            expected_sig
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sign_usd_class_transfer_action() -> Result<()> {
+        let wallet = get_wallet()?;
+
+        let usd_class_transfer = UsdClassTransfer {
+            signature_chain_id: 421614.into(),
+            hyperliquid_chain: "Testnet".to_string(),
+            amount: "1".to_string(),
+            to_perp: true,
+            nonce: 1690393044548,
+        };
+
+        // scripts/signing_vectors.py checks this against the Python SDK's
+        // `sign_user_signed_action` with its USD_CLASS_TRANSFER_SIGN_TYPES
+        let expected_sig = "f77964893919a5436f79bbadf5bf4d8b257efcca056dfd10b1ede57819b8ff9130289a50a4b08d01993f7a7435d88933f9021885a3c685d0b1b1094a5d461c381b";
+        assert_eq!(
+            sign_typed_data(&usd_class_transfer, &wallet)
+                .await?
+                .to_string(),
+            expected_sig
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sign_send_asset_action() -> Result<()> {
+        let wallet = get_wallet()?;
+
+        let send_asset = SendAsset {
+            signature_chain_id: 421614.into(),
+            hyperliquid_chain: "Testnet".to_string(),
+            destination: "0x0d1d9635d0640821d15e323ac8adadfa9c111414".to_string(),
+            source_dex: "".to_string(),
+            destination_dex: "spot".to_string(),
+            token: "USDC".to_string(),
+            amount: "1".to_string(),
+            from_sub_account: "".to_string(),
+            nonce: 1690393044548,
+        };
+
+        let expected_sig = "eb96384f3ebc9746f655ef90d54f3e239927cd05e0364dda376024b60a987c253813fcf0b1fe0f6e347aab0c51977a526d3ad792d683c512e6716476d0c670551c";
+        assert_eq!(
+            sign_typed_data(&send_asset, &wallet).await?.to_string(),
+            expected_sig
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sign_c_deposit_action() -> Result<()> {
+        let wallet = get_wallet()?;
+
+        let c_deposit = CDeposit {
+            signature_chain_id: 421614.into(),
+            hyperliquid_chain: "Testnet".to_string(),
+            wei: 100_000_000,
+            nonce: 1690393044548,
+        };
+
+        // scripts/signing_vectors.py checks this against the Python SDK's
+        // `sign_user_signed_action` with the CDeposit struct
+        let expected_sig = "236016598893e096f334bc60e1e70ad73558b073f439852a703617725fecf42535d704febfb519dd41f47d889899504fb10dee1dc1183131c936b5546159994b1b";
+        assert_eq!(
+            sign_typed_data(&c_deposit, &wallet).await?.to_string(),
+            expected_sig
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sign_c_withdraw_action() -> Result<()> {
+        let wallet = get_wallet()?;
+
+        let c_withdraw = CWithdraw {
+            signature_chain_id: 421614.into(),
+            hyperliquid_chain: "Testnet".to_string(),
+            wei: 100_000_000,
+            nonce: 1690393044548,
+        };
+
+        // scripts/signing_vectors.py checks this against the Python SDK's
+        // `sign_user_signed_action` with the CWithdraw struct
+        let expected_sig = "9cb3f555c1c4b9861fe4ee3a294add9cf9d9c362ef02e2e54c1735bf21d65574758a01f015666b7353d87f74e910a3080dabf31ee9b80dd4347b34f37f7c1c7f1b";
+        assert_eq!(
+            sign_typed_data(&c_withdraw, &wallet).await?.to_string(),
+            expected_sig
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sign_token_delegate_action() -> Result<()> {
+        let wallet = get_wallet()?;
+
+        let token_delegate = TokenDelegate {
+            signature_chain_id: 421614.into(),
+            hyperliquid_chain: "Testnet".to_string(),
+            validator: H160::from_str("0x5ac99df645f3414876c816caa18b2d234024b487")
+                .map_err(|e| Error::GenericParse(e.to_string()))?,
+            wei: 100_000_000,
+            is_undelegate: false,
+            nonce: 1690393044548,
+        };
+
+        // scripts/signing_vectors.py checks this against the Python SDK's
+        // `sign_user_signed_action` with its TOKEN_DELEGATE_TYPES
+        let expected_sig = "4ac2988821f24c19ab789fbe572d11f4b9b98fd98eef6f04f9ba858abb3494dc35458ac91367d27aca4a5819efcd1a20eb304c31ec9bf3f757270a84199dab101c";
+        assert_eq!(
+            sign_typed_data(&token_delegate, &wallet).await?.to_string(),
             expected_sig
         );
         Ok(())
@@ -1,4 +1,6 @@
 pub(crate) mod agent;
 mod create_signature;
+mod signer;
 
-pub(crate) use create_signature::{sign_l1_action, sign_typed_data};
+pub(crate) use create_signature::{recover_signer, sign_l1_action, sign_typed_data};
+pub use signer::HyperliquidSigner;
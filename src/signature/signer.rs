@@ -0,0 +1,42 @@
+use async_trait::async_trait;
+use ethers::{
+    signers::{LocalWallet, Signer},
+    types::{transaction::eip712::Eip712, Signature, H160, H256},
+};
+
+use crate::{prelude::*, signature::create_signature::sign_hash, Error};
+
+/// Anything able to produce Hyperliquid signatures.
+///
+/// `LocalWallet` is the default implementation. Implement this trait to sign through a KMS,
+/// a remote signing service or any custody setup where the private key never enters the
+/// process.
+#[async_trait]
+pub trait HyperliquidSigner: Send + Sync {
+    /// Address of the key producing the signatures
+    fn address(&self) -> H160;
+
+    /// Signs an already computed EIP-712 digest without any further hashing or prefixing
+    async fn sign_hash(&self, hash: H256) -> Result<Signature>;
+
+    /// Signs an EIP-712 typed struct. Signers that need to display the structured data can
+    /// override this, by default the digest is computed locally and passed to `sign_hash`.
+    async fn sign_typed_data<T: Eip712 + Send + Sync>(&self, payload: &T) -> Result<Signature> {
+        let encoded = payload
+            .encode_eip712()
+            .map_err(|e| Error::Eip712(e.to_string()))?;
+
+        self.sign_hash(H256::from(encoded)).await
+    }
+}
+
+#[async_trait]
+impl HyperliquidSigner for LocalWallet {
+    fn address(&self) -> H160 {
+        Signer::address(self)
+    }
+
+    async fn sign_hash(&self, hash: H256) -> Result<Signature> {
+        sign_hash(hash, self)
+    }
+}
@@ -1,6 +1,8 @@
 //! Bridge-specific functionality for Arbitrum <> Hyperliquid transfers
 
-use ethers::types::Address;
+use ethers::types::{Address, Bytes, TransactionRequest, U256};
+
+use crate::{prelude::*, Error};
 
 /// Bridge contract addresses
 pub const BRIDGE_MAINNET: &str = "0x2df1c51e09aecf9cacb7bc98cb1742757f163df7";
@@ -32,7 +34,7 @@ pub fn get_usdc_address(is_mainnet: bool) -> Address {
 }
 
 /// Create USDC transfer transaction data for ERC-20 transfer
-pub fn create_usdc_transfer_data(to: Address, amount: ethers::types::U256) -> String {
+pub fn create_usdc_transfer_data(to: Address, amount: U256) -> String {
     // ERC-20 transfer function selector: transfer(address,uint256)
     let selector = "a9059cbb";
     let to_padded = format!("{:064x}", to);
@@ -40,3 +42,25 @@ pub fn create_usdc_transfer_data(to: Address, amount: ethers::types::U256) -> St
 
     format!("0x{}{}{}", selector, to_padded, amount_padded)
 }
+
+/// Create the Arbitrum USDC transfer that deposits `amount` (6 decimals) into the bridge
+pub fn create_deposit_transaction(amount: U256, is_mainnet: bool) -> Result<TransactionRequest> {
+    // Validate minimum deposit amount (5 USDC)
+    if amount < U256::from(MIN_DEPOSIT_USDC) {
+        return Err(Error::GenericParse(format!(
+            "Amount {} is below minimum deposit of {} USDC",
+            amount,
+            MIN_DEPOSIT_USDC as f64 / 1_000_000.0
+        )));
+    }
+
+    let data = create_usdc_transfer_data(get_bridge_address(is_mainnet), amount);
+    let data = hex::decode(&data[2..]).map_err(|e| Error::GenericParse(e.to_string()))?;
+    let chain_id = if is_mainnet { 42161u64 } else { 421614u64 };
+
+    Ok(TransactionRequest::new()
+        .to(get_usdc_address(is_mainnet))
+        .data(Bytes::from(data))
+        .value(0)
+        .chain_id(chain_id))
+}
@@ -2,26 +2,27 @@ use crate::prelude::Result;
 use crate::req::HttpClient;
 use crate::BaseUrl;
 use crate::InfoClient;
+use crate::MetaSnapshot;
 use crate::PerpMeta;
 use ethers::types::H160;
 use reqwest::Client;
-use std::collections::HashMap;
+use serde::de::DeserializeOwned;
+use std::{collections::HashMap, sync::Arc};
 
-// Add new imports for the prepare_unsigned_order method
 use super::bridge;
 use super::components::UnsignedTransactionComponents;
-use crate::exchange::{ApproveBuilderFee, BuilderInfo};
+use super::multi_sig::MultiSigTransaction;
+use super::summary::SigningSummary;
+use crate::exchange::{ActionBuilder, BuilderInfo};
 use crate::helpers::generate_random_key;
-use crate::helpers::next_nonce;
-use crate::signature::agent::l1::Agent as L1Agent;
+use crate::nonce::{check_nonce_window, default_nonce_manager, NonceManager};
 use crate::{
-    Actions, ApproveAgent, BulkCancel, BulkModify, BulkOrder, CancelRequest, ClientCancelRequest,
-    ClientModifyRequest, ClientOrderRequest, ModifyRequest, SpotSend, UpdateIsolatedMargin,
-    UpdateLeverage, UsdSend, VaultTransfer, Withdraw3,
+    Actions, BracketOrder, ClientCancelRequest, ClientCancelRequestCloid, ClientModifyRequest,
+    ClientOrderRequest, ClientTwapCancelRequest, ClientTwapRequest, Error, ExchangeResponseStatus,
+    Grouping, MultiSigSigners,
 };
 use ethers::signers::{LocalWallet, Signer};
-use ethers::types::transaction::eip712::Eip712;
-use ethers::types::U256;
+use ethers::types::{Signature, H256, U256};
 
 #[derive(Debug)]
 pub struct UnsignedTransactionBuilder {
@@ -29,49 +30,145 @@ pub struct UnsignedTransactionBuilder {
     pub meta: Vec<PerpMeta>,
     pub vault_address: Option<H160>,
     pub coin_to_asset: HashMap<String, u32>,
+    pub nonce_manager: Arc<dyn NonceManager>,
+    /// Address of the external signer, keying its nonce sequence. Components prepared without
+    /// one share a single sequence.
+    pub signer_address: Option<H160>,
+    /// Timestamp (ms) after which the exchange rejects the prepared actions
+    pub expires_after: Option<u64>,
 }
 
 impl UnsignedTransactionBuilder {
+    /// Fetches the spot meta, and the perp meta of every dex unless `meta_override` is given.
+    /// Use `from_snapshot` to build it without any network access.
     pub async fn new(
         client: Option<Client>,
         base_url_override: Option<BaseUrl>,
         meta_override: Option<Vec<PerpMeta>>,
         vault_address: Option<H160>,
     ) -> Result<Self> {
-        let client = client.unwrap_or_default();
         let base_url = base_url_override.unwrap_or(BaseUrl::Mainnet);
 
-        let info_for_setup = InfoClient::new(None, Some(base_url)).await?;
-        let meta = if let Some(m) = meta_override {
-            m
-        } else {
-            info_for_setup.global_meta().await?
+        // Fetch on the caller's connection pool
+        let info_client = InfoClient::from_http_client(&HttpClient {
+            client: client.clone().unwrap_or_default(),
+            base_url: base_url.get_url(),
+        });
+        let perp_meta = match meta_override {
+            Some(perp_meta) => perp_meta,
+            None => info_client.global_meta().await?,
+        };
+        let snapshot = MetaSnapshot {
+            perp_meta,
+            spot_meta: info_client.spot_meta().await?,
         };
 
-        let mut coin_to_asset = HashMap::new();
-        for perp_meta in meta.iter() {
-            let hl_market_id_additive = if let Some(dex) = &perp_meta.dex {
-                100000 + dex.id * 10000
-            } else {
-                0
-            };
-            for (i, asset_meta) in perp_meta.meta.universe.iter().enumerate() {
-                coin_to_asset.insert(asset_meta.name.clone(), hl_market_id_additive + i as u32);
-            }
-        }
-        coin_to_asset = info_for_setup
-            .spot_meta()
-            .await?
-            .add_pair_and_name_to_index_map(coin_to_asset);
+        Ok(Self::from_snapshot(
+            client,
+            Some(base_url),
+            snapshot,
+            vault_address,
+        ))
+    }
 
-        Ok(UnsignedTransactionBuilder {
+    /// Builds the transaction builder from a previously saved `MetaSnapshot` without any
+    /// network access, e.g. inside an air-gapped signing service
+    pub fn from_snapshot(
+        client: Option<Client>,
+        base_url_override: Option<BaseUrl>,
+        snapshot: MetaSnapshot,
+        vault_address: Option<H160>,
+    ) -> Self {
+        let client = client.unwrap_or_default();
+        let base_url = base_url_override.unwrap_or(BaseUrl::Mainnet);
+        let coin_to_asset = snapshot.coin_to_asset();
+
+        UnsignedTransactionBuilder {
             http_client: HttpClient {
                 client,
                 base_url: base_url.get_url(),
             },
-            meta,
+            meta: snapshot.perp_meta,
             vault_address,
             coin_to_asset,
+            nonce_manager: default_nonce_manager(),
+            signer_address: None,
+            expires_after: None,
+        }
+    }
+
+    /// Replaces the process-wide default nonce manager, e.g. to inject a clock or persist nonces
+    pub fn with_nonce_manager(mut self, nonce_manager: Arc<dyn NonceManager>) -> Self {
+        self.nonce_manager = nonce_manager;
+        self
+    }
+
+    /// Keys nonces by the address that will sign the prepared components
+    pub fn with_signer_address(mut self, signer_address: H160) -> Self {
+        self.signer_address = Some(signer_address);
+        self
+    }
+
+    /// See `ExchangeClient::with_expires_after`
+    pub fn with_expires_after(mut self, expires_after: Option<u64>) -> Self {
+        self.expires_after = expires_after;
+        self
+    }
+
+    fn next_nonce(&self) -> Result<u64> {
+        self.nonce_manager
+            .next_nonce(self.signer_address.unwrap_or_default())
+    }
+
+    fn actions(&self) -> ActionBuilder<'_> {
+        ActionBuilder {
+            coin_to_asset: &self.coin_to_asset,
+            is_mainnet: self.http_client.is_mainnet(),
+        }
+    }
+
+    /// Computes the digest an external signer has to sign for `action`
+    pub(crate) fn components(
+        &self,
+        action: Actions,
+        nonce: u64,
+    ) -> Result<UnsignedTransactionComponents> {
+        self.components_expiring(action, nonce, self.expires_after)
+    }
+
+    fn components_expiring(
+        &self,
+        action: Actions,
+        nonce: u64,
+        expires_after: Option<u64>,
+    ) -> Result<UnsignedTransactionComponents> {
+        let is_mainnet = self.http_client.is_mainnet();
+        let expires_after = action.payload_expires_after(expires_after);
+        let digest_to_sign =
+            action.signing_digest(nonce, self.vault_address, expires_after, is_mainnet)?;
+        let (eip712_domain_chain_id, eip712_hyperliquid_chain_name) = if action.is_user_signed() {
+            (
+                self.actions().signature_chain_id(),
+                Some(self.actions().hyperliquid_chain()),
+            )
+        } else {
+            (U256::from(1337), None)
+        };
+
+        // Serialize action to JSON for the caller
+        let action_payload_json =
+            serde_json::to_value(&action).map_err(|e| Error::JsonParse(e.to_string()))?;
+
+        Ok(UnsignedTransactionComponents {
+            action_payload_json,
+            nonce,
+            digest_to_sign,
+            vault_address: action.payload_vault_address(self.vault_address),
+            expires_after,
+            eip712_domain_chain_id: Some(eip712_domain_chain_id),
+            eip712_hyperliquid_chain_name,
+            is_l1_agent_signature: !action.is_user_signed(),
+            is_mainnet: self.http_client.is_mainnet(),
         })
     }
 
@@ -90,51 +187,36 @@ impl UnsignedTransactionBuilder {
         grouping: Option<String>,
         builder: Option<BuilderInfo>,
     ) -> Result<UnsignedTransactionComponents> {
-        // Convert the ClientOrderRequest to OrderRequest using the coin_to_asset mapping
-        let order_request = order.convert(&self.coin_to_asset)?;
-
-        // Create the action
-        let action = Actions::Order(BulkOrder {
-            orders: vec![order_request],
-            grouping: grouping.unwrap_or_else(|| "na".to_string()),
+        let action = self.actions().order(
+            vec![order],
+            grouping.unwrap_or_else(|| Grouping::Na.into()),
             builder,
-        });
-
-        // Generate nonce
-        let nonce = next_nonce();
-
-        // Compute the action hash for L1 agent signing
-        let connection_id = action.hash(nonce, self.vault_address)?;
-
-        // Create L1 Agent for signing
-        let agent = L1Agent {
-            source: if self.http_client.is_mainnet() {
-                "a"
-            } else {
-                "b"
-            }
-            .to_string(),
-            connection_id,
-        };
-
-        // Get the typed data hash
-        let digest = agent
-            .encode_eip712()
-            .map_err(|e| crate::Error::Eip712(e.to_string()))?;
+        )?;
+        self.components(action, self.next_nonce()?)
+    }
 
-        // Serialize action to JSON for the caller
-        let action_json =
-            serde_json::to_value(&action).map_err(|e| crate::Error::JsonParse(e.to_string()))?;
+    /// Prepare several orders as one action, e.g. an entry order with its TP/SL legs
+    /// using `Grouping::NormalTpsl`
+    pub async fn prepare_unsigned_bulk_order(
+        &self,
+        orders: Vec<ClientOrderRequest>,
+        grouping: Grouping,
+        builder: Option<BuilderInfo>,
+    ) -> Result<UnsignedTransactionComponents> {
+        let action = self.actions().order(orders, grouping.into(), builder)?;
+        self.components(action, self.next_nonce()?)
+    }
 
-        Ok(UnsignedTransactionComponents {
-            action_payload_json: action_json,
-            nonce,
-            digest_to_sign: ethers::types::H256::from(digest),
-            vault_address: self.vault_address,
-            eip712_domain_chain_id: Some(ethers::types::U256::from(1337)),
-            eip712_hyperliquid_chain_name: None,
-            is_l1_agent_signature: true,
-        })
+    /// Prepare the entry and TP/SL legs of `bracket` as one `normalTpsl` bulk order. Once
+    /// posted, `BracketOrder::results` splits the statuses back into the legs.
+    pub async fn prepare_unsigned_bracket_order(
+        &self,
+        bracket: &BracketOrder,
+        builder: Option<BuilderInfo>,
+    ) -> Result<UnsignedTransactionComponents> {
+        let legs = bracket.legs(self.meta.iter().flat_map(|perp| &perp.meta.universe))?;
+        self.prepare_unsigned_bulk_order(legs, Grouping::NormalTpsl, builder)
+            .await
     }
 
     pub async fn prepare_unsigned_usdc_transfer(
@@ -142,98 +224,42 @@ impl UnsignedTransactionBuilder {
         amount_str: &str,
         destination_str: &str,
     ) -> Result<UnsignedTransactionComponents> {
-        let timestamp = next_nonce();
-        let hyperliquid_chain_name = if self.http_client.is_mainnet() {
-            "Mainnet".to_string()
-        } else {
-            "Testnet".to_string()
-        };
-        let signature_chain_id = if self.http_client.is_mainnet() {
-            U256::from(42161) // Arbitrum mainnet
-        } else {
-            U256::from(421614) // Arbitrum testnet
-        };
-
-        let usd_send_action = UsdSend {
-            signature_chain_id,
-            hyperliquid_chain: hyperliquid_chain_name.clone(),
-            destination: destination_str.to_string(),
-            amount: amount_str.to_string(),
-            time: timestamp,
-        };
-
-        let action_payload_json = serde_json::to_value(Actions::UsdSend(usd_send_action.clone()))
-            .map_err(|e| crate::Error::JsonParse(e.to_string()))?;
-
-        let digest_to_sign = ethers::types::H256::from(
-            usd_send_action
-                .encode_eip712()
-                .map_err(|e| crate::Error::Eip712(e.to_string()))?,
-        );
-
-        Ok(UnsignedTransactionComponents {
-            action_payload_json,
-            nonce: timestamp,
-            digest_to_sign,
-            vault_address: self.vault_address,
-            eip712_domain_chain_id: Some(signature_chain_id),
-            eip712_hyperliquid_chain_name: Some(hyperliquid_chain_name),
-            is_l1_agent_signature: false,
-        })
+        let timestamp = self.next_nonce()?;
+        let action = self
+            .actions()
+            .usd_send(amount_str, destination_str, timestamp);
+        self.components(action, timestamp)
     }
 
     pub async fn prepare_unsigned_cancel(
         &self,
         cancel: ClientCancelRequest,
     ) -> Result<UnsignedTransactionComponents> {
-        let nonce = next_nonce();
-
-        let &asset_index = self
-            .coin_to_asset
-            .get(&cancel.asset)
-            .ok_or(crate::Error::AssetNotFound)?;
-
-        let cancel_request = CancelRequest {
-            asset: asset_index,
-            oid: cancel.oid,
-        };
-
-        let action = Actions::Cancel(BulkCancel {
-            cancels: vec![cancel_request],
-        });
-
-        // Compute the action hash for L1 agent signing
-        let connection_id = action.hash(nonce, self.vault_address)?;
-
-        // Create L1 Agent for signing
-        let agent = L1Agent {
-            source: if self.http_client.is_mainnet() {
-                "a"
-            } else {
-                "b"
-            }
-            .to_string(),
-            connection_id,
-        };
+        self.prepare_unsigned_bulk_cancel(vec![cancel]).await
+    }
 
-        // Get the typed data hash
-        let digest = agent
-            .encode_eip712()
-            .map_err(|e| crate::Error::Eip712(e.to_string()))?;
+    pub async fn prepare_unsigned_bulk_cancel(
+        &self,
+        cancels_client: Vec<ClientCancelRequest>,
+    ) -> Result<UnsignedTransactionComponents> {
+        let action = self.actions().cancel(cancels_client)?;
+        self.components(action, self.next_nonce()?)
+    }
 
-        // Serialize action to JSON for the caller
-        let action_json =
-            serde_json::to_value(&action).map_err(|e| crate::Error::JsonParse(e.to_string()))?;
+    pub async fn prepare_unsigned_cancel_by_cloid(
+        &self,
+        cancel: ClientCancelRequestCloid,
+    ) -> Result<UnsignedTransactionComponents> {
+        self.prepare_unsigned_bulk_cancel_by_cloid(vec![cancel])
+            .await
+    }
 
-        Ok(UnsignedTransactionComponents {
-            action_payload_json: action_json,
-            nonce,
-            digest_to_sign: ethers::types::H256::from(digest),
-            vault_address: self.vault_address,
-            eip712_domain_chain_id: Some(ethers::types::U256::from(1337)),
-            eip712_hyperliquid_chain_name: None,
-            is_l1_agent_signature: true,
-        })
+    pub async fn prepare_unsigned_bulk_cancel_by_cloid(
+        &self,
+        cancels: Vec<ClientCancelRequestCloid>,
+    ) -> Result<UnsignedTransactionComponents> {
+        let action = self.actions().cancel_by_cloid(cancels)?;
+        self.components(action, self.next_nonce()?)
     }
 
     pub async fn prepare_unsigned_withdraw(
@@ -241,44 +267,9 @@ impl UnsignedTransactionBuilder {
         amount: &str,
         destination: &str,
     ) -> Result<UnsignedTransactionComponents> {
-        let timestamp = next_nonce();
-        let hyperliquid_chain_name = if self.http_client.is_mainnet() {
-            "Mainnet".to_string()
-        } else {
-            "Testnet".to_string()
-        };
-        let signature_chain_id = if self.http_client.is_mainnet() {
-            U256::from(42161) // Arbitrum mainnet
-        } else {
-            U256::from(421614) // Arbitrum testnet
-        };
-
-        let withdraw_action = Withdraw3 {
-            signature_chain_id,
-            hyperliquid_chain: hyperliquid_chain_name.clone(),
-            destination: destination.to_string(),
-            amount: amount.to_string(),
-            time: timestamp,
-        };
-
-        let action_payload_json = serde_json::to_value(Actions::Withdraw3(withdraw_action.clone()))
-            .map_err(|e| crate::Error::JsonParse(e.to_string()))?;
-
-        let digest_to_sign = ethers::types::H256::from(
-            withdraw_action
-                .encode_eip712()
-                .map_err(|e| crate::Error::Eip712(e.to_string()))?,
-        );
-
-        Ok(UnsignedTransactionComponents {
-            action_payload_json,
-            nonce: timestamp,
-            digest_to_sign,
-            vault_address: self.vault_address,
-            eip712_domain_chain_id: Some(signature_chain_id),
-            eip712_hyperliquid_chain_name: Some(hyperliquid_chain_name),
-            is_l1_agent_signature: false,
-        })
+        let timestamp = self.next_nonce()?;
+        let action = self.actions().withdraw(amount, destination, timestamp);
+        self.components(action, timestamp)
     }
 
     pub async fn prepare_unsigned_update_leverage(
@@ -287,51 +278,8 @@ impl UnsignedTransactionBuilder {
         asset: &str,
         is_cross: bool,
     ) -> Result<UnsignedTransactionComponents> {
-        let nonce = next_nonce();
-
-        let &asset_index = self
-            .coin_to_asset
-            .get(asset)
-            .ok_or(crate::Error::AssetNotFound)?;
-
-        let action = Actions::UpdateLeverage(UpdateLeverage {
-            asset: asset_index,
-            is_cross,
-            leverage,
-        });
-
-        // Compute the action hash for L1 agent signing
-        let connection_id = action.hash(nonce, self.vault_address)?;
-
-        // Create L1 Agent for signing
-        let agent = L1Agent {
-            source: if self.http_client.is_mainnet() {
-                "a"
-            } else {
-                "b"
-            }
-            .to_string(),
-            connection_id,
-        };
-
-        // Get the typed data hash
-        let digest = agent
-            .encode_eip712()
-            .map_err(|e| crate::Error::Eip712(e.to_string()))?;
-
-        // Serialize action to JSON for the caller
-        let action_json =
-            serde_json::to_value(&action).map_err(|e| crate::Error::JsonParse(e.to_string()))?;
-
-        Ok(UnsignedTransactionComponents {
-            action_payload_json: action_json,
-            nonce,
-            digest_to_sign: ethers::types::H256::from(digest),
-            vault_address: self.vault_address,
-            eip712_domain_chain_id: Some(ethers::types::U256::from(1337)),
-            eip712_hyperliquid_chain_name: None,
-            is_l1_agent_signature: true,
-        })
+        let action = self.actions().update_leverage(leverage, asset, is_cross)?;
+        self.components(action, self.next_nonce()?)
     }
 
     /// Convenience method for updating leverage in isolated margin mode
@@ -350,57 +298,11 @@ impl UnsignedTransactionBuilder {
         asset: &str,
         margin_to_add: String,
     ) -> Result<UnsignedTransactionComponents> {
-        let nonce = next_nonce();
-
-        let &asset_index = self
-            .coin_to_asset
-            .get(asset)
-            .ok_or(crate::Error::AssetNotFound)?;
-
-        // Parse the margin amount and convert to micro USDC (6 decimal places)
-        let margin_amount: f64 = margin_to_add
-            .parse()
-            .map_err(|_| crate::Error::FloatStringParse)?;
-        let ntli = (margin_amount * 1_000_000.0).round() as i64;
-
-        let action = Actions::UpdateIsolatedMargin(UpdateIsolatedMargin {
-            asset: asset_index,
-            is_buy: true, // Always true for adding margin
-            ntli,
-        });
-
-        // Compute the action hash for L1 agent signing
-        let connection_id = action.hash(nonce, self.vault_address)?;
-
-        // Create L1 Agent for signing
-        let agent = L1Agent {
-            source: if self.http_client.is_mainnet() {
-                "a"
-            } else {
-                "b"
-            }
-            .to_string(),
-            connection_id,
-        };
-
-        // Get the typed data hash
-        let digest = agent
-            .encode_eip712()
-            .map_err(|e| crate::Error::Eip712(e.to_string()))?;
-
-        // Serialize action to JSON for the caller
-        let action_json =
-            serde_json::to_value(&action).map_err(|e| crate::Error::JsonParse(e.to_string()))?;
-
-        Ok(UnsignedTransactionComponents {
-            action_payload_json: action_json,
-            nonce,
-            digest_to_sign: ethers::types::H256::from(digest),
-            vault_address: self.vault_address,
-            eip712_domain_chain_id: Some(ethers::types::U256::from(1337)),
-            eip712_hyperliquid_chain_name: None,
-            is_l1_agent_signature: true,
-        })
+        let margin_amount: f64 = margin_to_add.parse().map_err(|_| Error::FloatStringParse)?;
+        let action = self
+            .actions()
+            .update_isolated_margin(margin_amount, asset)?;
+        self.components(action, self.next_nonce()?)
     }
 
     pub async fn prepare_unsigned_spot_transfer(
@@ -409,261 +311,233 @@ impl UnsignedTransactionBuilder {
         destination: &str,
         token: &str,
     ) -> Result<UnsignedTransactionComponents> {
-        let timestamp = next_nonce();
-        let hyperliquid_chain_name = if self.http_client.is_mainnet() {
-            "Mainnet".to_string()
-        } else {
-            "Testnet".to_string()
-        };
-        let signature_chain_id = if self.http_client.is_mainnet() {
-            U256::from(42161) // Arbitrum mainnet
-        } else {
-            U256::from(421614) // Arbitrum testnet
-        };
-
-        let spot_send_action = SpotSend {
-            signature_chain_id,
-            hyperliquid_chain: hyperliquid_chain_name.clone(),
-            destination: destination.to_string(),
-            token: token.to_string(),
-            amount: amount.to_string(),
-            time: timestamp,
-        };
-
-        let action_payload_json = serde_json::to_value(Actions::SpotSend(spot_send_action.clone()))
-            .map_err(|e| crate::Error::JsonParse(e.to_string()))?;
+        let timestamp = self.next_nonce()?;
+        let action = self
+            .actions()
+            .spot_send(amount, destination, token, timestamp);
+        self.components(action, timestamp)
+    }
 
-        let digest_to_sign = ethers::types::H256::from(
-            spot_send_action
-                .encode_eip712()
-                .map_err(|e| crate::Error::Eip712(e.to_string()))?,
+    /// Prepare a `sendAsset` transfer between perp dexs, spot and other users, from the vault
+    /// or sub-account when a vault address is set. The main perp dex is `""` and the spot
+    /// balance `"spot"`.
+    pub async fn prepare_unsigned_send_asset(
+        &self,
+        destination: &str,
+        source_dex: &str,
+        destination_dex: &str,
+        token: &str,
+        amount: &str,
+    ) -> Result<UnsignedTransactionComponents> {
+        let timestamp = self.next_nonce()?;
+        let action = self.actions().send_asset(
+            destination,
+            source_dex,
+            destination_dex,
+            token,
+            amount,
+            self.vault_address,
+            timestamp,
         );
+        self.components(action, timestamp)
+    }
 
-        Ok(UnsignedTransactionComponents {
-            action_payload_json,
-            nonce: timestamp,
-            digest_to_sign,
-            vault_address: self.vault_address,
-            eip712_domain_chain_id: Some(signature_chain_id),
-            eip712_hyperliquid_chain_name: Some(hyperliquid_chain_name),
-            is_l1_agent_signature: false,
-        })
+    /// Prepare a transfer of USDC between the spot and perp balances, of the vault or
+    /// sub-account when a vault address is set
+    pub async fn prepare_unsigned_class_transfer(
+        &self,
+        usdc: f64,
+        to_perp: bool,
+    ) -> Result<UnsignedTransactionComponents> {
+        let timestamp = self.next_nonce()?;
+        let action =
+            self.actions()
+                .usd_class_transfer(usdc, to_perp, self.vault_address, timestamp);
+        self.components(action, timestamp)
     }
 
     pub async fn prepare_unsigned_vault_transfer(
         &self,
         is_deposit: bool,
         usd: u64,
-        vault_address: Option<ethers::types::H160>,
+        vault_address: Option<H160>,
     ) -> Result<UnsignedTransactionComponents> {
-        let nonce = next_nonce();
-
-        let action = Actions::VaultTransfer(VaultTransfer {
-            vault_address: vault_address.unwrap_or_default(),
-            is_deposit,
-            usd,
-        });
-
-        // Compute the action hash for L1 agent signing
-        let connection_id = action.hash(nonce, self.vault_address)?;
-
-        // Create L1 Agent for signing
-        let agent = L1Agent {
-            source: if self.http_client.is_mainnet() {
-                "a"
-            } else {
-                "b"
-            }
-            .to_string(),
-            connection_id,
-        };
-
-        // Get the typed data hash
-        let digest = agent
-            .encode_eip712()
-            .map_err(|e| crate::Error::Eip712(e.to_string()))?;
-
-        // Serialize action to JSON for the caller
-        let action_json =
-            serde_json::to_value(&action).map_err(|e| crate::Error::JsonParse(e.to_string()))?;
-
-        Ok(UnsignedTransactionComponents {
-            action_payload_json: action_json,
-            nonce,
-            digest_to_sign: ethers::types::H256::from(digest),
-            vault_address: self.vault_address,
-            eip712_domain_chain_id: Some(ethers::types::U256::from(1337)),
-            eip712_hyperliquid_chain_name: None,
-            is_l1_agent_signature: true,
-        })
+        let vault_address = self
+            .vault_address
+            .or(vault_address)
+            .ok_or(Error::VaultAddressNotFound)?;
+        let action = self
+            .actions()
+            .vault_transfer(vault_address, is_deposit, usd);
+        self.components(action, self.next_nonce()?)
     }
 
     pub async fn prepare_unsigned_modify_order(
         &self,
         modify_request_client: ClientModifyRequest,
     ) -> Result<UnsignedTransactionComponents> {
-        // Convert the ClientOrderRequest to OrderRequest using the coin_to_asset mapping
-        let order_request = modify_request_client.order.convert(&self.coin_to_asset)?;
-
-        // Create the ModifyRequest
-        let transformed_modify = ModifyRequest {
-            oid: modify_request_client.oid,
-            order: order_request,
-        };
-
-        // Create the action
-        let action = Actions::BatchModify(BulkModify {
-            modifies: vec![transformed_modify],
-        });
+        self.prepare_unsigned_bulk_modify(vec![modify_request_client])
+            .await
+    }
 
-        // Generate nonce
-        let nonce = next_nonce();
+    pub async fn prepare_unsigned_bulk_modify(
+        &self,
+        modifies: Vec<ClientModifyRequest>,
+    ) -> Result<UnsignedTransactionComponents> {
+        let action = self.actions().modify(modifies)?;
+        self.components(action, self.next_nonce()?)
+    }
 
-        // Compute the action hash for L1 agent signing
-        let connection_id = action.hash(nonce, self.vault_address)?;
+    pub async fn prepare_unsigned_twap_order(
+        &self,
+        twap: ClientTwapRequest,
+    ) -> Result<UnsignedTransactionComponents> {
+        let action = self.actions().twap_order(twap)?;
+        self.components(action, self.next_nonce()?)
+    }
 
-        // Create L1 Agent for signing
-        let agent = L1Agent {
-            source: if self.http_client.is_mainnet() {
-                "a"
-            } else {
-                "b"
-            }
-            .to_string(),
-            connection_id,
-        };
+    pub async fn prepare_unsigned_twap_cancel(
+        &self,
+        cancel: ClientTwapCancelRequest,
+    ) -> Result<UnsignedTransactionComponents> {
+        let action = self.actions().twap_cancel(cancel)?;
+        self.components(action, self.next_nonce()?)
+    }
 
-        // Get the typed data hash
-        let digest = agent
-            .encode_eip712()
-            .map_err(|e| crate::Error::Eip712(e.to_string()))?;
+    /// See `ExchangeClient::staking_deposit`
+    pub async fn prepare_unsigned_staking_deposit(
+        &self,
+        wei: u64,
+    ) -> Result<UnsignedTransactionComponents> {
+        let nonce = self.next_nonce()?;
+        let action = self.actions().c_deposit(wei, nonce);
+        self.components(action, nonce)
+    }
 
-        // Serialize action to JSON for the caller
-        let action_json =
-            serde_json::to_value(&action).map_err(|e| crate::Error::JsonParse(e.to_string()))?;
+    /// See `ExchangeClient::staking_withdraw`
+    pub async fn prepare_unsigned_staking_withdraw(
+        &self,
+        wei: u64,
+    ) -> Result<UnsignedTransactionComponents> {
+        let nonce = self.next_nonce()?;
+        let action = self.actions().c_withdraw(wei, nonce);
+        self.components(action, nonce)
+    }
 
-        Ok(UnsignedTransactionComponents {
-            action_payload_json: action_json,
-            nonce,
-            digest_to_sign: ethers::types::H256::from(digest),
-            vault_address: self.vault_address,
-            eip712_domain_chain_id: Some(ethers::types::U256::from(1337)),
-            eip712_hyperliquid_chain_name: None,
-            is_l1_agent_signature: true,
-        })
+    /// See `ExchangeClient::token_delegate`
+    pub async fn prepare_unsigned_token_delegate(
+        &self,
+        validator: H160,
+        wei: u64,
+        is_undelegate: bool,
+    ) -> Result<UnsignedTransactionComponents> {
+        let nonce = self.next_nonce()?;
+        let action = self
+            .actions()
+            .token_delegate(validator, wei, is_undelegate, nonce);
+        self.components(action, nonce)
     }
 
-    pub async fn prepare_unsigned_bulk_cancel(
+    /// See `ExchangeClient::create_vault`
+    pub async fn prepare_unsigned_create_vault(
         &self,
-        cancels_client: Vec<ClientCancelRequest>,
+        name: String,
+        description: String,
+        initial_usd: u64,
     ) -> Result<UnsignedTransactionComponents> {
-        // Transform Vec<ClientCancelRequest> to Vec<CancelRequest>
-        let mut transformed_cancels = Vec::new();
-        for cancel_client in cancels_client {
-            let &asset_index = self
-                .coin_to_asset
-                .get(&cancel_client.asset)
-                .ok_or(crate::Error::AssetNotFound)?;
-            transformed_cancels.push(CancelRequest {
-                asset: asset_index,
-                oid: cancel_client.oid,
-            });
-        }
+        let nonce = self.next_nonce()?;
+        let action = self
+            .actions()
+            .create_vault(name, description, initial_usd, nonce);
+        self.components(action, nonce)
+    }
 
-        // Create the action
-        let action = Actions::Cancel(BulkCancel {
-            cancels: transformed_cancels,
-        });
+    /// See `ExchangeClient::vault_modify`
+    pub async fn prepare_unsigned_vault_modify(
+        &self,
+        vault_address: H160,
+        allow_deposits: Option<bool>,
+        always_close_on_withdraw: Option<bool>,
+    ) -> Result<UnsignedTransactionComponents> {
+        let action =
+            self.actions()
+                .vault_modify(vault_address, allow_deposits, always_close_on_withdraw);
+        self.components(action, self.next_nonce()?)
+    }
 
-        // Generate nonce
-        let nonce = next_nonce();
+    pub async fn prepare_unsigned_vault_distribute(
+        &self,
+        vault_address: H160,
+        usd: u64,
+    ) -> Result<UnsignedTransactionComponents> {
+        let action = self.actions().vault_distribute(vault_address, usd);
+        self.components(action, self.next_nonce()?)
+    }
 
-        // Compute the action hash for L1 agent signing
-        let connection_id = action.hash(nonce, self.vault_address)?;
+    pub async fn prepare_unsigned_create_sub_account(
+        &self,
+        name: String,
+    ) -> Result<UnsignedTransactionComponents> {
+        let action = self.actions().create_sub_account(name);
+        self.components(action, self.next_nonce()?)
+    }
 
-        // Create L1 Agent for signing
-        let agent = L1Agent {
-            source: if self.http_client.is_mainnet() {
-                "a"
-            } else {
-                "b"
-            }
-            .to_string(),
-            connection_id,
-        };
+    /// See `ExchangeClient::sub_account_transfer`
+    pub async fn prepare_unsigned_sub_account_transfer(
+        &self,
+        sub_account_user: H160,
+        is_deposit: bool,
+        usd: u64,
+    ) -> Result<UnsignedTransactionComponents> {
+        let action = self
+            .actions()
+            .sub_account_transfer(sub_account_user, is_deposit, usd);
+        self.components(action, self.next_nonce()?)
+    }
 
-        // Get the typed data hash
-        let digest = agent
-            .encode_eip712()
-            .map_err(|e| crate::Error::Eip712(e.to_string()))?;
+    pub async fn prepare_unsigned_sub_account_spot_transfer(
+        &self,
+        sub_account_user: H160,
+        is_deposit: bool,
+        token: &str,
+        amount: &str,
+    ) -> Result<UnsignedTransactionComponents> {
+        let action =
+            self.actions()
+                .sub_account_spot_transfer(sub_account_user, is_deposit, token, amount);
+        self.components(action, self.next_nonce()?)
+    }
 
-        // Serialize action to JSON for the caller
-        let action_json =
-            serde_json::to_value(&action).map_err(|e| crate::Error::JsonParse(e.to_string()))?;
+    pub async fn prepare_unsigned_set_referrer(
+        &self,
+        code: String,
+    ) -> Result<UnsignedTransactionComponents> {
+        let action = self.actions().set_referrer(code);
+        self.components(action, self.next_nonce()?)
+    }
 
-        Ok(UnsignedTransactionComponents {
-            action_payload_json: action_json,
-            nonce,
-            digest_to_sign: ethers::types::H256::from(digest),
-            vault_address: self.vault_address,
-            eip712_domain_chain_id: Some(ethers::types::U256::from(1337)),
-            eip712_hyperliquid_chain_name: None,
-            is_l1_agent_signature: true,
-        })
+    /// See `ExchangeClient::schedule_cancel`
+    pub async fn prepare_unsigned_schedule_cancel(
+        &self,
+        time: Option<u64>,
+    ) -> Result<UnsignedTransactionComponents> {
+        let action = self.actions().schedule_cancel(time);
+        self.components(action, self.next_nonce()?)
     }
 
     pub async fn prepare_unsigned_approve_agent(
         &self,
     ) -> Result<(String, UnsignedTransactionComponents)> {
-        let nonce = next_nonce();
-        let hyperliquid_chain_name = if self.http_client.is_mainnet() {
-            "Mainnet".to_string()
-        } else {
-            "Testnet".to_string()
-        };
-        let signature_chain_id = if self.http_client.is_mainnet() {
-            U256::from(42161) // Arbitrum mainnet
-        } else {
-            U256::from(421614) // Arbitrum testnet
-        };
-
         // Generate a random private key for the agent (like in ExchangeClient::approve_agent)
         let key = hex::encode(generate_random_key()?);
         let agent_address = key
             .parse::<LocalWallet>()
-            .map_err(|e| crate::Error::PrivateKeyParse(e.to_string()))?
+            .map_err(|e| Error::PrivateKeyParse(e.to_string()))?
             .address();
 
-        let approve_agent_action = ApproveAgent {
-            signature_chain_id,
-            hyperliquid_chain: hyperliquid_chain_name.clone(),
-            agent_address,
-            agent_name: None,
-            nonce,
-        };
-
-        let action_payload_json =
-            serde_json::to_value(Actions::ApproveAgent(approve_agent_action.clone()))
-                .map_err(|e| crate::Error::JsonParse(e.to_string()))?;
-
-        let digest_to_sign = ethers::types::H256::from(
-            approve_agent_action
-                .encode_eip712()
-                .map_err(|e| crate::Error::Eip712(e.to_string()))?,
-        );
-
-        Ok((
-            key,
-            UnsignedTransactionComponents {
-                action_payload_json,
-                nonce,
-                digest_to_sign,
-                vault_address: self.vault_address,
-                eip712_domain_chain_id: Some(signature_chain_id),
-                eip712_hyperliquid_chain_name: Some(hyperliquid_chain_name),
-                is_l1_agent_signature: false,
-            },
-        ))
+        let nonce = self.next_nonce()?;
+        let action = self.actions().approve_agent(agent_address, None, nonce);
+        Ok((key, self.components(action, nonce)?))
     }
 
     /// Prepare an unsigned approve builder fee transaction
@@ -672,90 +546,129 @@ impl UnsignedTransactionBuilder {
         builder: String,
         max_fee_rate: String,
     ) -> Result<UnsignedTransactionComponents> {
-        let timestamp = next_nonce();
-        let hyperliquid_chain_name = if self.http_client.is_mainnet() {
-            "Mainnet".to_string()
-        } else {
-            "Testnet".to_string()
-        };
-        let signature_chain_id = if self.http_client.is_mainnet() {
-            U256::from(42161) // Arbitrum mainnet
-        } else {
-            U256::from(421614) // Arbitrum testnet
-        };
+        let timestamp = self.next_nonce()?;
+        let action = self
+            .actions()
+            .approve_builder_fee(&builder, max_fee_rate, timestamp);
+        self.components(action, timestamp)
+    }
 
-        let approve_action = ApproveBuilderFee {
-            signature_chain_id,
-            hyperliquid_chain: hyperliquid_chain_name.clone(),
-            builder,
-            max_fee_rate,
-            nonce: timestamp,
-        };
+    /// Prepare the conversion of the signing account into a multi-sig user controlled by
+    /// `authorized_users`, `threshold` of which must sign each of its actions
+    pub async fn prepare_unsigned_convert_to_multi_sig_user(
+        &self,
+        authorized_users: Vec<H160>,
+        threshold: usize,
+    ) -> Result<UnsignedTransactionComponents> {
+        let nonce = self.next_nonce()?;
+        let action = self.actions().convert_to_multi_sig_user(
+            MultiSigSigners {
+                authorized_users,
+                threshold,
+            },
+            nonce,
+        )?;
+        self.components(action, nonce)
+    }
 
-        let action_payload_json =
-            serde_json::to_value(Actions::ApproveBuilderFee(approve_action.clone()))
-                .map_err(|e| crate::Error::JsonParse(e.to_string()))?;
+    /// Starts collecting signatures for a prepared action of `multi_sig_user`, to be
+    /// submitted by `outer_signer`
+    pub fn prepare_multi_sig_transaction(
+        &self,
+        inner: &UnsignedTransactionComponents,
+        multi_sig_user: H160,
+        outer_signer: H160,
+    ) -> Result<MultiSigTransaction> {
+        if inner.digest_to_sign.is_zero() {
+            return Err(Error::GenericRequest(
+                "Components do not describe an exchange action".to_string(),
+            ));
+        }
 
-        let digest_to_sign = ethers::types::H256::from(
-            approve_action
-                .encode_eip712()
-                .map_err(|e| crate::Error::Eip712(e.to_string()))?,
+        Ok(MultiSigTransaction {
+            multi_sig_user,
+            outer_signer,
+            action_payload_json: inner.action_payload_json.clone(),
+            nonce: inner.nonce,
+            vault_address: inner.vault_address,
+            expires_after: inner.expires_after,
+            is_mainnet: self.http_client.is_mainnet(),
+            signatures: Vec::new(),
+        })
+    }
+
+    /// Wraps a multi-sig transaction and its collected signatures into the `multiSig`
+    /// action, to be signed by the outer signer and passed to `submit_signed`
+    pub fn prepare_unsigned_multi_sig(
+        &self,
+        transaction: &MultiSigTransaction,
+    ) -> Result<UnsignedTransactionComponents> {
+        let action = self.actions().multi_sig(
+            transaction.multi_sig_user,
+            transaction.outer_signer,
+            transaction.inner_action()?,
+            transaction.signatures.clone(),
         );
+        // The outer signature must cover the expiry the authorized users signed
+        self.components_expiring(action, transaction.nonce, transaction.expires_after)
+    }
 
-        Ok(UnsignedTransactionComponents {
-            action_payload_json,
-            nonce: timestamp,
-            digest_to_sign,
-            vault_address: self.vault_address,
-            eip712_domain_chain_id: Some(signature_chain_id),
-            eip712_hyperliquid_chain_name: Some(hyperliquid_chain_name),
-            is_l1_agent_signature: false,
-        })
+    /// Decodes prepared components into a human-readable `SigningSummary`, resolving coin
+    /// names through this builder's asset mapping
+    pub fn summarize(&self, components: &UnsignedTransactionComponents) -> Result<SigningSummary> {
+        components.summarize(&self.coin_to_asset)
+    }
+
+    /// Submit previously prepared components together with the signature produced
+    /// externally over `digest_to_sign`
+    pub async fn submit_signed(
+        &self,
+        components: UnsignedTransactionComponents,
+        signature: Signature,
+    ) -> Result<ExchangeResponseStatus> {
+        self.submit_signed_as(components, signature).await
+    }
+
+    /// See `ExchangeClient::submit_signed_as`
+    pub async fn submit_signed_as<T: DeserializeOwned>(
+        &self,
+        components: UnsignedTransactionComponents,
+        signature: Signature,
+    ) -> Result<ExchangeResponseStatus<T>> {
+        let payload = components.into_exchange_payload(signature)?;
+        check_nonce_window(payload.nonce, self.nonce_manager.now_ms())?;
+        payload.post(&self.http_client).await
     }
 
     /// Prepare unsigned USDC transfer to bridge contract for deposit
     pub async fn prepare_unsigned_bridge_deposit(
         &self,
-        amount: ethers::types::U256,
+        amount: U256,
     ) -> Result<UnsignedTransactionComponents> {
         let is_mainnet = self.http_client.is_mainnet();
-        let bridge_address = bridge::get_bridge_address(is_mainnet);
-        let usdc_address = bridge::get_usdc_address(is_mainnet);
+        let transaction = bridge::create_deposit_transaction(amount, is_mainnet)?;
 
-        // Validate minimum deposit amount (5 USDC)
-        let min_deposit = ethers::types::U256::from(bridge::MIN_DEPOSIT_USDC);
-        if amount < min_deposit {
-            return Err(crate::Error::GenericParse(format!(
-                "Amount {} is below minimum deposit of {} USDC",
-                amount,
-                bridge::MIN_DEPOSIT_USDC as f64 / 1_000_000.0
-            )));
-        }
-
-        // Create USDC transfer transaction data
-        let transfer_data = bridge::create_usdc_transfer_data(bridge_address, amount);
-
-        let chain_id = if is_mainnet { "0xa4b1" } else { "0x66eee" };
+        let usdc_address = bridge::get_usdc_address(is_mainnet);
+        let transfer_data = transaction.data.unwrap_or_default();
+        let chain_id = transaction.chain_id.unwrap_or_default();
 
         let transaction_data = serde_json::json!({
             "to": format!("0x{:040x}", usdc_address),
-            "data": transfer_data,
+            "data": format!("{transfer_data}"),
             "value": "0x0",
-            "chainId": chain_id
+            "chainId": format!("{chain_id:#x}")
         });
 
         Ok(UnsignedTransactionComponents {
             action_payload_json: transaction_data,
-            nonce: 0,                                    // Will be set by the client
-            digest_to_sign: ethers::types::H256::zero(), // Will be computed by the client
+            nonce: 0,                     // Will be set by the client
+            digest_to_sign: H256::zero(), // Will be computed by the client
             vault_address: None,
-            eip712_domain_chain_id: Some(if is_mainnet {
-                ethers::types::U256::from(42161)
-            } else {
-                ethers::types::U256::from(421614)
-            }),
+            expires_after: None,
+            eip712_domain_chain_id: Some(U256::from(chain_id.as_u64())),
             eip712_hyperliquid_chain_name: None,
             is_l1_agent_signature: false,
+            is_mainnet: self.http_client.is_mainnet(),
         })
     }
 }
@@ -763,8 +676,15 @@ impl UnsignedTransactionBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{ClientLimit, ClientOrder};
-    use ethers::types::H256;
+    use crate::exchange::ExchangePayload;
+    use crate::{
+        BracketOrder, BracketPrice, ClientLimit, ClientOrder, ClientTrigger, ExchangeClient,
+        LocalNonceManager, MAX_NONCE_AGE_MS,
+    };
+    use ethers::types::transaction::eip712::{Eip712, TypedData};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+    use tokio::sync::mpsc;
     use uuid::Uuid;
 
     #[tokio::test]
@@ -793,6 +713,581 @@ mod tests {
         }
     }
 
+    fn offline_builder_snapshot() -> MetaSnapshot {
+        serde_json::from_value(serde_json::json!({
+            "perpMeta": [{
+                "dex": null,
+                "meta": {
+                    "universe": [{"name": "ETH", "szDecimals": 4, "maxLeverage": 25, "marginTableId": 25}],
+                    "marginTables": [],
+                    "collateralToken": 0,
+                },
+            }],
+            "spotMeta": {"universe": [], "tokens": []},
+        }))
+        .unwrap()
+    }
+
+    fn offline_builder() -> UnsignedTransactionBuilder {
+        UnsignedTransactionBuilder::from_snapshot(
+            None,
+            Some(BaseUrl::Testnet),
+            offline_builder_snapshot(),
+            None,
+        )
+    }
+
+    #[tokio::test]
+    async fn test_injected_nonce_manager() {
+        let signer = H160::from_low_u64_be(1);
+        let builder = offline_builder()
+            .with_nonce_manager(Arc::new(LocalNonceManager::new(|| 1_700_000_000_000)))
+            .with_signer_address(signer);
+
+        let first = builder
+            .prepare_unsigned_update_leverage(5, "ETH", true)
+            .await
+            .unwrap();
+        let second = builder
+            .prepare_unsigned_usdc_transfer("1", "0x0d1d9635d0640821d15e323ac8adadfa9c111414")
+            .await
+            .unwrap();
+        assert_eq!(first.nonce, 1_700_000_000_000);
+        assert_eq!(second.nonce, 1_700_000_000_001);
+        assert_eq!(second.action_payload_json["time"], 1_700_000_000_001u64);
+
+        // Signed too long ago to be accepted, rejected before anything is sent
+        let stale = offline_builder().with_nonce_manager(Arc::new(LocalNonceManager::new(|| {
+            1_700_000_000_000 + MAX_NONCE_AGE_MS + 1
+        })));
+        let result = stale
+            .submit_signed(
+                first,
+                Signature {
+                    r: 1.into(),
+                    s: 1.into(),
+                    v: 27,
+                },
+            )
+            .await;
+        assert!(matches!(result, Err(Error::Nonce(_))));
+    }
+
+    #[tokio::test]
+    async fn test_expires_after() {
+        let expires_after = 1_700_000_060_000;
+        let builder = offline_builder()
+            .with_nonce_manager(Arc::new(LocalNonceManager::new(|| 1_700_000_000_000)))
+            .with_expires_after(Some(expires_after));
+
+        let leverage = builder
+            .prepare_unsigned_update_leverage(5, "ETH", true)
+            .await
+            .unwrap();
+        assert_eq!(leverage.expires_after, Some(expires_after));
+        leverage.verify_digest().unwrap();
+        let action: Actions = serde_json::from_value(leverage.action_payload_json.clone()).unwrap();
+        assert_ne!(
+            leverage.digest_to_sign,
+            action
+                .signing_digest(leverage.nonce, None, None, false)
+                .unwrap()
+        );
+
+        // User-signed actions do not support an expiry
+        let transfer = builder
+            .prepare_unsigned_usdc_transfer("1", "0x0d1d9635d0640821d15e323ac8adadfa9c111414")
+            .await
+            .unwrap();
+        assert_eq!(transfer.expires_after, None);
+    }
+
+    #[tokio::test]
+    async fn test_unsigned_transaction_builder_from_snapshot() {
+        let builder = offline_builder();
+        assert_eq!(builder.coin_to_asset.get("ETH"), Some(&0));
+
+        let order = ClientOrderRequest {
+            asset: "ETH".to_string(),
+            is_buy: true,
+            reduce_only: false,
+            limit_px: 2000.0,
+            sz: 0.1,
+            cloid: None,
+            order_type: ClientOrder::Limit(ClientLimit {
+                tif: "Gtc".to_string(),
+            }),
+        };
+        let components = builder.prepare_unsigned_order(order, None).await.unwrap();
+        assert!(components.is_l1_agent_signature);
+        assert_eq!(components.action_payload_json["orders"][0]["a"], 0);
+    }
+
+    /// Serves `/exchange` on a local port like the exchange would, handing over every payload
+    /// posted to it
+    async fn capture_exchange() -> (String, mpsc::UnboundedReceiver<ExchangePayload>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let (sender, receiver) = mpsc::unbounded_channel();
+
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut request = Vec::new();
+                let body = loop {
+                    let mut chunk = [0; 4096];
+                    let read = stream.read(&mut chunk).await.unwrap();
+                    assert!(read > 0, "request ended early");
+                    request.extend_from_slice(&chunk[..read]);
+                    let request = String::from_utf8_lossy(&request);
+                    let Some((head, body)) = request.split_once("\r\n\r\n") else {
+                        continue;
+                    };
+                    let length = head
+                        .lines()
+                        .find_map(|line| {
+                            let (name, value) = line.split_once(':')?;
+                            name.eq_ignore_ascii_case("content-length")
+                                .then(|| value.trim().parse::<usize>().unwrap())
+                        })
+                        .unwrap();
+                    if body.len() >= length {
+                        break body.to_string();
+                    }
+                };
+                let payload: ExchangePayload = serde_json::from_str(&body).unwrap();
+
+                // Orders need a status per order, e.g. for the legs of a bracket order
+                let response = match payload.action["orders"].as_array() {
+                    Some(orders) => serde_json::json!({
+                        "status": "ok",
+                        "response": {"type": "order", "data": {"statuses": (0..orders.len())
+                            .map(|oid| serde_json::json!({"resting": {"oid": oid}}))
+                            .collect::<Vec<_>>()}},
+                    }),
+                    None => serde_json::json!({"status": "ok", "response": {"type": "default"}}),
+                }
+                .to_string();
+                stream
+                    .write_all(
+                        format!(
+                            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+                             Content-Length: {}\r\nConnection: close\r\n\r\n{response}",
+                            response.len()
+                        )
+                        .as_bytes(),
+                    )
+                    .await
+                    .unwrap();
+                sender.send(payload).unwrap();
+            }
+        });
+
+        (url, receiver)
+    }
+
+    #[tokio::test]
+    async fn test_exchange_client_parity() {
+        let wallet: LocalWallet =
+            "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e"
+                .parse()
+                .unwrap();
+        let vault_address: H160 = "0x1719884eb866cb12b2287399b15f7db5e7d775ea"
+            .parse()
+            .unwrap();
+        let destination = "0x0d1d9635d0640821d15e323ac8adadfa9c111414";
+        let purr = "PURR:0xc4bf3f870c0e9465323c0b6ed28096c2";
+        // Separate managers on the same frozen clock hand out the same nonce sequence
+        let nonce_manager = || Arc::new(LocalNonceManager::new(|| 1_700_000_000_000));
+
+        let (url, mut payloads) = capture_exchange().await;
+        let mut client = ExchangeClient::from_snapshot(
+            Some(Client::builder().no_proxy().build().unwrap()),
+            wallet.clone(),
+            Some(BaseUrl::Localhost),
+            offline_builder_snapshot(),
+            Some(vault_address),
+        )
+        .unwrap()
+        .with_nonce_manager(nonce_manager());
+        client.http_client.base_url = url;
+        let builder = UnsignedTransactionBuilder::from_snapshot(
+            None,
+            Some(BaseUrl::Localhost),
+            offline_builder_snapshot(),
+            Some(vault_address),
+        )
+        .with_nonce_manager(nonce_manager())
+        .with_signer_address(wallet.address());
+
+        let order = ClientOrderRequest {
+            asset: "ETH".to_string(),
+            is_buy: true,
+            reduce_only: false,
+            limit_px: 2000.0,
+            sz: 0.1,
+            cloid: Some(Uuid::from_u128(1)),
+            order_type: ClientOrder::Limit(ClientLimit {
+                tif: "Gtc".to_string(),
+            }),
+        };
+        let bracket = BracketOrder {
+            asset: "ETH".to_string(),
+            is_buy: true,
+            sz: 0.1,
+            entry_px: 2000.0,
+            tif: "Gtc".to_string(),
+            cloid: None,
+            take_profit: Some(BracketPrice::Percent(2.0)),
+            stop_loss: Some(BracketPrice::Price(1900.0)),
+        };
+        let cancel = ClientCancelRequest {
+            asset: "ETH".to_string(),
+            oid: 1,
+        };
+        let cancel_by_cloid = ClientCancelRequestCloid {
+            asset: "ETH".to_string(),
+            cloid: Uuid::from_u128(1),
+        };
+        let modify = || ClientModifyRequest {
+            oid: 1,
+            order: order.clone(),
+        };
+        let twap = ClientTwapRequest {
+            asset: "ETH".to_string(),
+            is_buy: true,
+            sz: 1.5,
+            reduce_only: false,
+            minutes: 30,
+            randomize: true,
+        };
+        let twap_cancel = ClientTwapCancelRequest {
+            asset: "ETH".to_string(),
+            twap_id: 7,
+        };
+        let validator: H160 = destination.parse().unwrap();
+
+        let prepared = vec![
+            builder
+                .prepare_unsigned_order(order.clone(), None)
+                .await
+                .unwrap(),
+            builder
+                .prepare_unsigned_bracket_order(&bracket, None)
+                .await
+                .unwrap(),
+            builder
+                .prepare_unsigned_cancel(cancel.clone())
+                .await
+                .unwrap(),
+            builder
+                .prepare_unsigned_cancel_by_cloid(cancel_by_cloid.clone())
+                .await
+                .unwrap(),
+            builder
+                .prepare_unsigned_modify_order(modify())
+                .await
+                .unwrap(),
+            builder
+                .prepare_unsigned_update_leverage(5, "ETH", false)
+                .await
+                .unwrap(),
+            builder
+                .prepare_unsigned_update_isolated_margin("ETH", "10.5".to_string())
+                .await
+                .unwrap(),
+            builder
+                .prepare_unsigned_twap_order(twap.clone())
+                .await
+                .unwrap(),
+            builder
+                .prepare_unsigned_twap_cancel(twap_cancel.clone())
+                .await
+                .unwrap(),
+            builder
+                .prepare_unsigned_usdc_transfer("1", destination)
+                .await
+                .unwrap(),
+            builder
+                .prepare_unsigned_withdraw("5", destination)
+                .await
+                .unwrap(),
+            builder
+                .prepare_unsigned_spot_transfer("1", destination, purr)
+                .await
+                .unwrap(),
+            builder
+                .prepare_unsigned_class_transfer(1.5, true)
+                .await
+                .unwrap(),
+            builder
+                .prepare_unsigned_send_asset(destination, "", "spot", "USDC", "10")
+                .await
+                .unwrap(),
+            builder
+                .prepare_unsigned_vault_transfer(true, 1_000_000, None)
+                .await
+                .unwrap(),
+            builder
+                .prepare_unsigned_staking_deposit(100_000_000)
+                .await
+                .unwrap(),
+            builder
+                .prepare_unsigned_token_delegate(validator, 100_000_000, false)
+                .await
+                .unwrap(),
+            builder
+                .prepare_unsigned_sub_account_transfer(validator, true, 1_000_000)
+                .await
+                .unwrap(),
+            builder
+                .prepare_unsigned_schedule_cancel(Some(1_700_000_060_000))
+                .await
+                .unwrap(),
+        ];
+
+        client.order(order.clone(), None).await.unwrap();
+        client.bracket_order(bracket, None).await.unwrap();
+        client.cancel(cancel, None).await.unwrap();
+        client.cancel_by_cloid(cancel_by_cloid, None).await.unwrap();
+        client.modify(modify(), None).await.unwrap();
+        client.update_leverage(5, "ETH", false, None).await.unwrap();
+        client
+            .update_isolated_margin(10.5, "ETH", None)
+            .await
+            .unwrap();
+        client.twap_order(twap, None).await.unwrap();
+        client.twap_cancel(twap_cancel, None).await.unwrap();
+        client.usdc_transfer("1", destination, None).await.unwrap();
+        client
+            .withdraw_from_bridge("5", destination, None)
+            .await
+            .unwrap();
+        client
+            .spot_transfer("1", destination, purr, None)
+            .await
+            .unwrap();
+        client.class_transfer(1.5, true, None).await.unwrap();
+        client
+            .send_asset(destination, "", "spot", "USDC", "10", None)
+            .await
+            .unwrap();
+        client
+            .vault_transfer(true, 1_000_000, None, None)
+            .await
+            .unwrap();
+        client.staking_deposit(100_000_000, None).await.unwrap();
+        client
+            .token_delegate(validator, 100_000_000, false, None)
+            .await
+            .unwrap();
+        client
+            .sub_account_transfer(validator, true, 1_000_000, None)
+            .await
+            .unwrap();
+        client
+            .schedule_cancel(Some(1_700_000_060_000), None)
+            .await
+            .unwrap();
+
+        for components in prepared {
+            let payload = payloads.recv().await.unwrap();
+            let action_type = components.action_payload_json["type"].clone();
+
+            assert_eq!(payload.action, components.action_payload_json);
+            assert_eq!(payload.nonce, components.nonce, "{action_type}");
+            assert_eq!(
+                payload.vault_address, components.vault_address,
+                "{action_type}"
+            );
+            assert_eq!(
+                payload.expires_after, components.expires_after,
+                "{action_type}"
+            );
+            assert_eq!(
+                components.recover_signer(&payload.signature).unwrap(),
+                wallet.address(),
+                "{action_type} signed over a different digest"
+            );
+            components.verify_digest().unwrap();
+
+            // Transfers of a vault or sub-account name it in the action instead
+            let expected_vault = match action_type.as_str() {
+                Some("usdClassTransfer" | "sendAsset") => None,
+                _ => Some(vault_address),
+            };
+            assert_eq!(payload.vault_address, expected_vault, "{action_type}");
+        }
+        assert!(payloads.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_multi_sig_flow() {
+        let builder = offline_builder();
+        let wallets: Vec<LocalWallet> = [
+            "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e",
+            "0123456789012345678901234567890123456789012345678901234567890123",
+            "1111111111111111111111111111111111111111111111111111111111111111",
+        ]
+        .iter()
+        .map(|key| key.parse().unwrap())
+        .collect();
+        let authorized = MultiSigSigners {
+            authorized_users: wallets[..2].iter().map(Signer::address).collect(),
+            threshold: 2,
+        };
+        let multi_sig_user: H160 = "0x0d1d9635d0640821d15e323ac8adadfa9c111414"
+            .parse()
+            .unwrap();
+        let outer_signer = wallets[0].address();
+
+        let inner = builder
+            .prepare_unsigned_update_leverage(5, "ETH", true)
+            .await
+            .unwrap();
+        let mut transaction = builder
+            .prepare_multi_sig_transaction(&inner, multi_sig_user, outer_signer)
+            .unwrap();
+
+        // Each signer may run in its own process, so pass the transaction around as JSON
+        for wallet in &wallets[..2] {
+            let mut received: MultiSigTransaction =
+                serde_json::from_str(&serde_json::to_string(&transaction).unwrap()).unwrap();
+            let digest = received.digest_to_sign().unwrap();
+            let signature = wallet.sign_hash(digest).unwrap();
+            assert_eq!(received.add_signature(signature).unwrap(), wallet.address());
+            assert!(received.add_signature(signature).is_err());
+            transaction = received;
+
+            if transaction.signatures.len() < authorized.threshold {
+                assert!(transaction.verify_signers(&authorized).is_err());
+            }
+        }
+        transaction.verify_signers(&authorized).unwrap();
+
+        let mut duplicated = transaction.clone();
+        duplicated.signatures = vec![transaction.signatures[0]; 2];
+        assert!(duplicated.verify_signers(&authorized).is_err());
+
+        let mut unauthorized = transaction.clone();
+        let signature = wallets[2]
+            .sign_hash(unauthorized.digest_to_sign().unwrap())
+            .unwrap();
+        unauthorized.add_signature(signature).unwrap();
+        assert!(matches!(
+            unauthorized.verify_signers(&authorized),
+            Err(Error::UnauthorizedSigner { .. })
+        ));
+
+        let components = builder.prepare_unsigned_multi_sig(&transaction).unwrap();
+        assert_eq!(components.action_payload_json["type"], "multiSig");
+        assert_eq!(
+            components.action_payload_json["payload"]["action"],
+            inner.action_payload_json
+        );
+        assert_eq!(components.nonce, inner.nonce);
+        components.verify_digest().unwrap();
+
+        let typed_data: TypedData =
+            serde_json::from_value(components.to_typed_data_json().unwrap()).unwrap();
+        assert_eq!(
+            H256::from(typed_data.encode_eip712().unwrap()),
+            components.digest_to_sign
+        );
+
+        let outer_signature = wallets[0].sign_hash(components.digest_to_sign).unwrap();
+        assert_eq!(
+            components.recover_signer(&outer_signature).unwrap(),
+            outer_signer
+        );
+
+        let summary = builder.summarize(&components).unwrap();
+        assert!(summary.digest_matches);
+        assert!(summary.details[0]
+            .to_string()
+            .ends_with("with 2 signatures:\n  Set ETH leverage to 5x cross"));
+    }
+
+    #[tokio::test]
+    async fn test_prepare_unsigned_bracket_order() {
+        let builder = offline_builder();
+        let bracket = BracketOrder::new("ETH", false, 0.1, 2000.0)
+            .take_profit(BracketPrice::Percent(5.0))
+            .stop_loss(BracketPrice::Price(2100.0));
+
+        let components = builder
+            .prepare_unsigned_bracket_order(&bracket, None)
+            .await
+            .unwrap();
+
+        let action = &components.action_payload_json;
+        assert_eq!(action["grouping"], "normalTpsl");
+        let orders = action["orders"].as_array().unwrap();
+        assert_eq!(orders.len(), 3);
+        assert_eq!(orders[0]["b"], false);
+        assert_eq!(orders[0]["t"]["limit"]["tif"], "Gtc");
+        for (order, tpsl, trigger_px) in [(&orders[1], "tp", "1900"), (&orders[2], "sl", "2100")] {
+            assert_eq!(order["b"], true);
+            assert_eq!(order["r"], true);
+            assert_eq!(order["s"], "0.1");
+            assert_eq!(order["t"]["trigger"]["tpsl"], tpsl);
+            assert_eq!(order["t"]["trigger"]["triggerPx"], trigger_px);
+        }
+        components.verify_digest().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_prepare_unsigned_bulk_order() {
+        let builder = offline_builder();
+        let order = |is_buy: bool, reduce_only: bool, order_type: ClientOrder| ClientOrderRequest {
+            asset: "ETH".to_string(),
+            is_buy,
+            reduce_only,
+            limit_px: 2000.0,
+            sz: 0.1,
+            cloid: None,
+            order_type,
+        };
+        let trigger = |trigger_px: f64, tpsl: &str| {
+            ClientOrder::Trigger(ClientTrigger {
+                is_market: true,
+                trigger_px,
+                tpsl: tpsl.to_string(),
+            })
+        };
+
+        let components = builder
+            .prepare_unsigned_bulk_order(
+                vec![
+                    order(
+                        true,
+                        false,
+                        ClientOrder::Limit(ClientLimit {
+                            tif: "Gtc".to_string(),
+                        }),
+                    ),
+                    order(false, true, trigger(2200.0, "tp")),
+                    order(false, true, trigger(1800.0, "sl")),
+                ],
+                Grouping::NormalTpsl,
+                Some(BuilderInfo {
+                    builder: "0xF5Bc9107916B91A3Ea5966cd2e51655D21B7Eb02".to_string(),
+                    fee: 5,
+                }),
+            )
+            .await
+            .unwrap();
+
+        let action = &components.action_payload_json;
+        assert_eq!(action["grouping"], "normalTpsl");
+        assert_eq!(action["orders"].as_array().unwrap().len(), 3);
+        assert_eq!(action["orders"][1]["t"]["trigger"]["tpsl"], "tp");
+        assert_eq!(
+            action["builder"]["b"],
+            "0xf5bc9107916b91a3ea5966cd2e51655d21b7eb02"
+        );
+        components.verify_digest().unwrap();
+    }
+
     #[tokio::test]
     async fn test_unsigned_transaction_builder_with_vault() {
         let vault_addr = "0x1234567890123456789012345678901234567890"
@@ -1,5 +1,30 @@
-use ethers::types::{H160, H256, U256};
-use serde_json::Value;
+use ethers::types::{transaction::eip712::Eip712, Signature, H160, H256, U256};
+use serde_json::{json, Map, Value};
+
+use crate::{
+    exchange::{
+        Eip712Fields, ExchangePayload, SendMultiSig, UserSignedType, HYPERLIQUID_EIP_PREFIX,
+    },
+    prelude::*,
+    signature::{agent::l1, recover_signer},
+    Actions, Error,
+};
+
+const EIP712_DOMAIN_TYPE: [(&str, &str); 4] = [
+    ("name", "string"),
+    ("version", "string"),
+    ("chainId", "uint256"),
+    ("verifyingContract", "address"),
+];
+
+fn type_fields(fields: &[(&str, &str)]) -> Value {
+    Value::Array(
+        fields
+            .iter()
+            .map(|(name, ty)| json!({ "name": name, "type": ty }))
+            .collect(),
+    )
+}
 
 #[derive(Debug, Clone)]
 pub struct UnsignedTransactionComponents {
@@ -9,7 +34,570 @@ pub struct UnsignedTransactionComponents {
 
     // Optional context helpful for reconstructing the EIP-712 typed data or understanding the signature type
     pub vault_address: Option<H160>, // Vault address if applicable
+    pub expires_after: Option<u64>,  // Expiry (ms) covered by the signature, if any
     pub eip712_domain_chain_id: Option<U256>, // e.g., 421614 for Arbitrum or 1337 for L1 agent
     pub eip712_hyperliquid_chain_name: Option<String>, // "Mainnet" or "Testnet" for some EIP-712 structs
     pub is_l1_agent_signature: bool, // True if digest is for l1::Agent, false for direct EIP-712 on action
+    pub is_mainnet: bool,            // Network the digest was computed for
+}
+
+impl UnsignedTransactionComponents {
+    /// Combines the components with an externally produced signature into the
+    /// payload expected by the `/exchange` endpoint.
+    pub(crate) fn into_exchange_payload(self, signature: Signature) -> Result<ExchangePayload> {
+        // Bridge deposits are plain Arbitrum transactions and have nothing to post
+        if self.digest_to_sign.is_zero() {
+            return Err(Error::GenericRequest(
+                "Components do not describe an exchange action".to_string(),
+            ));
+        }
+
+        Ok(ExchangePayload {
+            action: self.action_payload_json,
+            signature,
+            nonce: self.nonce,
+            vault_address: self.vault_address,
+            expires_after: self.expires_after,
+        })
+    }
+
+    /// Returns the full EIP-712 document (domain, types, primaryType and message) that
+    /// hashes to `digest_to_sign`, in the format expected by `eth_signTypedData_v4`.
+    ///
+    /// Wallets that refuse to blind-sign a raw hash can be handed this JSON instead.
+    pub fn to_typed_data_json(&self) -> Result<Value> {
+        if self.digest_to_sign.is_zero() {
+            return Err(Error::Eip712(
+                "Components do not describe an exchange action".to_string(),
+            ));
+        }
+
+        let action = self.action()?;
+        if action.is_user_signed() {
+            self.user_signed_typed_data()
+        } else {
+            self.l1_agent_typed_data(&action)
+        }
+    }
+
+    /// Decodes the action payload, rejecting components whose `is_l1_agent_signature` flag
+    /// disagrees with the kind of action
+    fn action(&self) -> Result<Actions> {
+        let action: Actions = serde_json::from_value(self.action_payload_json.clone())
+            .map_err(|e| Error::JsonParse(e.to_string()))?;
+        if self.is_l1_agent_signature == action.is_user_signed() {
+            return Err(Error::Eip712(
+                "is_l1_agent_signature does not match the action payload".to_string(),
+            ));
+        }
+        Ok(action)
+    }
+
+    /// Recomputes the digest from the action payload for the network in `is_mainnet` and
+    /// checks it matches `digest_to_sign`
+    pub fn verify_digest(&self) -> Result<()> {
+        self.verified_action().map(|_| ())
+    }
+
+    fn verified_action(&self) -> Result<Actions> {
+        let action = self.action()?;
+        let digest = action.signing_digest(
+            self.nonce,
+            self.vault_address,
+            self.expires_after,
+            self.is_mainnet,
+        )?;
+        if digest == self.digest_to_sign {
+            Ok(action)
+        } else {
+            Err(Error::Eip712(
+                "Action payload does not match digest_to_sign".to_string(),
+            ))
+        }
+    }
+
+    /// Checks the digest against the action payload and recovers the address that
+    /// produced `signature` over it
+    pub fn recover_signer(&self, signature: &Signature) -> Result<H160> {
+        self.verify_digest()?;
+        recover_signer(self.digest_to_sign, signature)
+    }
+
+    /// Checks that `signature` was produced by `user`, or by one of the user's approved
+    /// agents for actions signed through the L1 agent wrapper. Agents may never sign
+    /// user-signed actions such as transfers and withdrawals.
+    ///
+    /// Returns the recovered signer.
+    pub fn verify_signer(
+        &self,
+        signature: &Signature,
+        user: H160,
+        approved_agents: &[H160],
+    ) -> Result<H160> {
+        // Decided from the action itself, as the components' flag is caller-supplied
+        let may_use_agent = !self.verified_action()?.is_user_signed();
+        let recovered = recover_signer(self.digest_to_sign, signature)?;
+        if recovered == user || (may_use_agent && approved_agents.contains(&recovered)) {
+            Ok(recovered)
+        } else {
+            Err(Error::UnauthorizedSigner { user, recovered })
+        }
+    }
+
+    fn l1_agent_typed_data(&self, action: &Actions) -> Result<Value> {
+        let connection_id = action.hash(self.nonce, self.vault_address, self.expires_after)?;
+        let source = if self.is_mainnet { "a" } else { "b" };
+        let digest = l1::Agent {
+            source: source.to_string(),
+            connection_id,
+        }
+        .encode_eip712()
+        .map_err(|e| Error::Eip712(e.to_string()))?;
+        if H256::from(digest) != self.digest_to_sign {
+            return Err(Error::Eip712(
+                "Action payload does not match digest_to_sign".to_string(),
+            ));
+        }
+
+        Ok(json!({
+            "domain": {
+                "name": "Exchange",
+                "version": "1",
+                "chainId": 1337,
+                "verifyingContract": "0x0000000000000000000000000000000000000000",
+            },
+            "types": {
+                "EIP712Domain": type_fields(&EIP712_DOMAIN_TYPE),
+                "Agent": type_fields(&[("source", "string"), ("connectionId", "bytes32")]),
+            },
+            "primaryType": "Agent",
+            "message": {
+                "source": source,
+                "connectionId": connection_id,
+            },
+        }))
+    }
+
+    fn user_signed_typed_data(&self) -> Result<Value> {
+        let chain_id = self
+            .eip712_domain_chain_id
+            .ok_or_else(|| Error::Eip712("Missing EIP-712 domain chain id".to_string()))?;
+
+        if self.action_payload_json.get("type").and_then(Value::as_str) == Some("multiSig") {
+            let action: Actions = serde_json::from_value(self.action_payload_json.clone())
+                .map_err(|e| Error::JsonParse(e.to_string()))?;
+            let Actions::MultiSig(multi_sig) = action else {
+                return Err(Error::Eip712(
+                    "Action payload is not a multiSig action".to_string(),
+                ));
+            };
+            let envelope = multi_sig.envelope(
+                self.nonce,
+                self.vault_address,
+                self.expires_after,
+                self.is_mainnet,
+            )?;
+            let message = json!({
+                "hyperliquidChain": envelope.hyperliquid_chain,
+                "multiSigActionHash": envelope.multi_sig_action_hash,
+                "nonce": envelope.nonce,
+            });
+            return typed_data_document(
+                SendMultiSig::TYPE_NAME,
+                SendMultiSig::FIELDS,
+                &message,
+                chain_id,
+                None,
+            );
+        }
+
+        user_signed_typed_data(&self.action_payload_json, chain_id, None)
+    }
+}
+
+/// Builds the EIP-712 document of a user-signed action from its JSON payload.
+///
+/// With `multi_sig` set to the multi-sig user and outer signer, the struct is extended
+/// with those two addresses, giving what the authorized users sign for a `MultiSig` action.
+pub(crate) fn user_signed_typed_data(
+    action_payload_json: &Value,
+    chain_id: U256,
+    multi_sig: Option<(H160, H160)>,
+) -> Result<Value> {
+    let action: Actions = serde_json::from_value(action_payload_json.clone())
+        .map_err(|e| Error::JsonParse(e.to_string()))?;
+    let (type_name, fields) = action
+        .user_signed_eip712_type()
+        .ok_or_else(|| Error::Eip712("Not a user-signed action".to_string()))?;
+    typed_data_document(type_name, fields, action_payload_json, chain_id, multi_sig)
+}
+
+/// The EIP-712 document of the `type_name` struct, taking the value of each field from
+/// `source`
+fn typed_data_document(
+    type_name: &str,
+    fields: Eip712Fields,
+    source: &Value,
+    chain_id: U256,
+    multi_sig: Option<(H160, H160)>,
+) -> Result<Value> {
+    let mut message = Map::new();
+    for (name, ty) in fields {
+        let value = match source.get(*name) {
+            Some(Value::Null) | None if *ty == "string" => Value::String(String::new()),
+            Some(value) => value.clone(),
+            None => return Err(Error::Eip712(format!("Action payload is missing {name}"))),
+        };
+        message.insert(name.to_string(), value);
+    }
+
+    let mut fields = fields.to_vec();
+    if let Some((multi_sig_user, outer_signer)) = multi_sig {
+        // Inserted right after hyperliquidChain
+        fields.splice(
+            1..1,
+            [
+                ("payloadMultiSigUser", "address"),
+                ("outerSigner", "address"),
+            ],
+        );
+        message.insert("payloadMultiSigUser".to_string(), json!(multi_sig_user));
+        message.insert("outerSigner".to_string(), json!(outer_signer));
+    }
+
+    let primary_type = format!("{HYPERLIQUID_EIP_PREFIX}{type_name}");
+    let mut types = Map::new();
+    types.insert("EIP712Domain".to_string(), type_fields(&EIP712_DOMAIN_TYPE));
+    types.insert(primary_type.clone(), type_fields(&fields));
+
+    Ok(json!({
+        "domain": {
+            "name": "HyperliquidSignTransaction",
+            "version": "1",
+            "chainId": chain_id.as_u64(),
+            "verifyingContract": "0x0000000000000000000000000000000000000000",
+        },
+        "types": types,
+        "primaryType": primary_type,
+        "message": message,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ApproveAgent, ApproveBuilderFee, CDeposit, CWithdraw, ConvertToMultiSigUser,
+        HyperliquidSigner, MultiSig, MultiSigPayload, SendAsset, SetReferrer, SpotSend,
+        TokenDelegate, UsdClassTransfer, UsdSend, Withdraw3,
+    };
+    use ethers::signers::{LocalWallet, WalletError};
+    use ethers::types::transaction::eip712::TypedData;
+
+    fn assert_typed_data_reproduces_digest(
+        components: &UnsignedTransactionComponents,
+    ) -> Result<()> {
+        let typed_data: TypedData = serde_json::from_value(components.to_typed_data_json()?)
+            .map_err(|e| Error::JsonParse(e.to_string()))?;
+        let digest = typed_data
+            .encode_eip712()
+            .map_err(|e| Error::Eip712(e.to_string()))?;
+        assert_eq!(H256::from(digest), components.digest_to_sign);
+        Ok(())
+    }
+
+    fn user_signed_components(action: Actions) -> Result<UnsignedTransactionComponents> {
+        Ok(UnsignedTransactionComponents {
+            action_payload_json: serde_json::to_value(&action)
+                .map_err(|e| Error::JsonParse(e.to_string()))?,
+            nonce: 1690393044548,
+            digest_to_sign: action.signing_digest(1690393044548, None, None, false)?,
+            vault_address: None,
+            expires_after: None,
+            eip712_domain_chain_id: Some(U256::from(421614)),
+            eip712_hyperliquid_chain_name: Some("Testnet".to_string()),
+            is_l1_agent_signature: false,
+            is_mainnet: false,
+        })
+    }
+
+    fn components(digest_to_sign: H256) -> UnsignedTransactionComponents {
+        UnsignedTransactionComponents {
+            action_payload_json: serde_json::json!({"type": "setReferrer", "code": "TEST"}),
+            nonce: 1583838,
+            digest_to_sign,
+            vault_address: Some(H160::from_low_u64_be(1)),
+            expires_after: Some(1583838 + 60_000),
+            eip712_domain_chain_id: Some(U256::from(1337)),
+            eip712_hyperliquid_chain_name: None,
+            is_l1_agent_signature: true,
+            is_mainnet: false,
+        }
+    }
+
+    #[test]
+    fn test_into_exchange_payload() -> Result<()> {
+        let signature = Signature {
+            r: U256::from(1),
+            s: U256::from(2),
+            v: 27,
+        };
+        let payload = components(H256::from_low_u64_be(1)).into_exchange_payload(signature)?;
+        let json = serde_json::to_value(&payload).map_err(|e| Error::JsonParse(e.to_string()))?;
+
+        assert_eq!(json["action"]["type"], "setReferrer");
+        assert_eq!(json["nonce"], 1583838);
+        assert_eq!(
+            json["vaultAddress"],
+            "0x0000000000000000000000000000000000000001"
+        );
+        assert_eq!(json["expiresAfter"], 1583838 + 60_000);
+        assert_eq!(json["signature"]["v"], 27);
+
+        assert!(components(H256::zero())
+            .into_exchange_payload(signature)
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_l1_agent_typed_data() -> Result<()> {
+        let action: Actions = serde_json::from_value(json!({
+            "type": "order",
+            "orders": [{"a": 1, "b": true, "p": "2000.0", "s": "3.5", "r": false, "t": {"limit": {"tif": "Ioc"}}}],
+            "grouping": "na",
+        }))
+        .map_err(|e| Error::JsonParse(e.to_string()))?;
+
+        for (vault_address, source) in [(None, "a"), (Some(H160::from_low_u64_be(1)), "b")] {
+            let connection_id = action.hash(1583838, vault_address, None)?;
+            let digest = l1::Agent {
+                source: source.to_string(),
+                connection_id,
+            }
+            .encode_eip712()
+            .map_err(|e| Error::Eip712(e.to_string()))?;
+            let components = UnsignedTransactionComponents {
+                action_payload_json: serde_json::to_value(&action)
+                    .map_err(|e| Error::JsonParse(e.to_string()))?,
+                nonce: 1583838,
+                digest_to_sign: H256::from(digest),
+                vault_address,
+                expires_after: None,
+                eip712_domain_chain_id: Some(U256::from(1337)),
+                eip712_hyperliquid_chain_name: None,
+                is_l1_agent_signature: true,
+                is_mainnet: source == "a",
+            };
+
+            let typed_data = components.to_typed_data_json()?;
+            assert_eq!(typed_data["primaryType"], "Agent");
+            assert_eq!(typed_data["message"]["source"], source);
+            assert_typed_data_reproduces_digest(&components)?;
+
+            // The source follows `is_mainnet`, so the other network's document is refused
+            let other_network = UnsignedTransactionComponents {
+                is_mainnet: source != "a",
+                ..components
+            };
+            assert!(other_network.to_typed_data_json().is_err());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_user_signed_typed_data() -> Result<()> {
+        let usd_send = UsdSend {
+            signature_chain_id: 421614.into(),
+            hyperliquid_chain: "Testnet".to_string(),
+            destination: "0x0D1d9635D0640821d15e323ac8AdADfA9c111414".to_string(),
+            amount: "1".to_string(),
+            time: 1690393044548,
+        };
+        let withdraw = Withdraw3 {
+            signature_chain_id: 421614.into(),
+            hyperliquid_chain: "Testnet".to_string(),
+            destination: "0x0D1d9635D0640821d15e323ac8AdADfA9c111414".to_string(),
+            amount: "1".to_string(),
+            time: 1690393044548,
+        };
+        let spot_send = SpotSend {
+            signature_chain_id: 421614.into(),
+            hyperliquid_chain: "Testnet".to_string(),
+            destination: "0x0D1d9635D0640821d15e323ac8AdADfA9c111414".to_string(),
+            token: "PURR:0xc4bf3f870c0e9465323c0b6ed28096c2".to_string(),
+            amount: "1".to_string(),
+            time: 1690393044548,
+        };
+        let usd_class_transfer = UsdClassTransfer {
+            signature_chain_id: 421614.into(),
+            hyperliquid_chain: "Testnet".to_string(),
+            amount: "1 subaccount:0x0000000000000000000000000000000000000001".to_string(),
+            to_perp: true,
+            nonce: 1690393044548,
+        };
+        let send_asset = SendAsset {
+            signature_chain_id: 421614.into(),
+            hyperliquid_chain: "Testnet".to_string(),
+            destination: "0x0d1d9635d0640821d15e323ac8adadfa9c111414".to_string(),
+            source_dex: "".to_string(),
+            destination_dex: "spot".to_string(),
+            token: "USDC".to_string(),
+            amount: "1".to_string(),
+            from_sub_account: "0x0000000000000000000000000000000000000001".to_string(),
+            nonce: 1690393044548,
+        };
+        let approve_agent = ApproveAgent {
+            signature_chain_id: 421614.into(),
+            hyperliquid_chain: "Testnet".to_string(),
+            agent_address: H160::from_low_u64_be(1),
+            agent_name: None,
+            nonce: 1690393044548,
+        };
+        let approve_builder_fee = ApproveBuilderFee {
+            signature_chain_id: 421614.into(),
+            hyperliquid_chain: "Testnet".to_string(),
+            builder: "0x1234567890123456789012345678901234567890".to_string(),
+            max_fee_rate: "0.001%".to_string(),
+            nonce: 1690393044548,
+        };
+
+        let convert_to_multi_sig_user = ConvertToMultiSigUser {
+            signature_chain_id: 421614.into(),
+            hyperliquid_chain: "Testnet".to_string(),
+            signers: r#"{"authorizedUsers":["0x0000000000000000000000000000000000000001"],"threshold":1}"#
+                .to_string(),
+            nonce: 1690393044548,
+        };
+        let c_deposit = CDeposit {
+            signature_chain_id: 421614.into(),
+            hyperliquid_chain: "Testnet".to_string(),
+            wei: 100_000_000,
+            nonce: 1690393044548,
+        };
+        let c_withdraw = CWithdraw {
+            signature_chain_id: 421614.into(),
+            hyperliquid_chain: "Testnet".to_string(),
+            wei: 100_000_000,
+            nonce: 1690393044548,
+        };
+        let token_delegate = TokenDelegate {
+            signature_chain_id: 421614.into(),
+            hyperliquid_chain: "Testnet".to_string(),
+            validator: H160::from_low_u64_be(1),
+            wei: 100_000_000,
+            is_undelegate: true,
+            nonce: 1690393044548,
+        };
+
+        let multi_sig = MultiSig {
+            signature_chain_id: 421614.into(),
+            signatures: vec![],
+            payload: MultiSigPayload {
+                multi_sig_user: H160::from_low_u64_be(1),
+                outer_signer: H160::from_low_u64_be(2),
+                action: Box::new(Actions::UsdSend(usd_send.clone())),
+            },
+        };
+
+        // Every user-signed variant, see `Actions::is_user_signed`
+        let actions = [
+            Actions::UsdSend(usd_send),
+            Actions::Withdraw3(withdraw),
+            Actions::SpotSend(spot_send),
+            Actions::UsdClassTransfer(usd_class_transfer),
+            Actions::SendAsset(send_asset),
+            Actions::CDeposit(c_deposit),
+            Actions::CWithdraw(c_withdraw),
+            Actions::TokenDelegate(token_delegate),
+            Actions::ApproveAgent(approve_agent),
+            Actions::ApproveBuilderFee(approve_builder_fee),
+            Actions::ConvertToMultiSigUser(convert_to_multi_sig_user),
+            Actions::MultiSig(multi_sig),
+        ];
+        for action in actions {
+            assert!(action.is_user_signed());
+            let components = user_signed_components(action)?;
+            assert_typed_data_reproduces_digest(&components)?;
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_verify_signer() -> Result<()> {
+        let user: LocalWallet = "e908f86dbb4d55ac876378565aafeabc187f6690f046459397b17d9b9a19688e"
+            .parse()
+            .map_err(|e: WalletError| Error::Wallet(e.to_string()))?;
+        let agent: LocalWallet = "0123456789012345678901234567890123456789012345678901234567890123"
+            .parse()
+            .map_err(|e: WalletError| Error::Wallet(e.to_string()))?;
+        let user_address = HyperliquidSigner::address(&user);
+        let agent_address = HyperliquidSigner::address(&agent);
+
+        let action = Actions::SetReferrer(SetReferrer {
+            code: "TEST".to_string(),
+        });
+        let l1_components = UnsignedTransactionComponents {
+            action_payload_json: serde_json::to_value(&action)
+                .map_err(|e| Error::JsonParse(e.to_string()))?,
+            nonce: 1583838,
+            digest_to_sign: action.signing_digest(1583838, None, None, false)?,
+            vault_address: None,
+            expires_after: None,
+            eip712_domain_chain_id: Some(U256::from(1337)),
+            eip712_hyperliquid_chain_name: None,
+            is_l1_agent_signature: true,
+            is_mainnet: false,
+        };
+        let signature = HyperliquidSigner::sign_hash(&agent, l1_components.digest_to_sign).await?;
+        assert_eq!(
+            l1_components.verify_signer(&signature, user_address, &[agent_address])?,
+            agent_address
+        );
+        assert!(matches!(
+            l1_components.verify_signer(&signature, user_address, &[]),
+            Err(Error::UnauthorizedSigner { .. })
+        ));
+
+        let mut tampered = l1_components.clone();
+        tampered.action_payload_json["code"] = "OTHER".into();
+        assert!(tampered.recover_signer(&signature).is_err());
+        // The digest of the same action on the other network is not accepted
+        let other_network = UnsignedTransactionComponents {
+            is_mainnet: true,
+            ..l1_components.clone()
+        };
+        assert!(other_network.verify_digest().is_err());
+
+        let usd_send = UsdSend {
+            signature_chain_id: 421614.into(),
+            hyperliquid_chain: "Testnet".to_string(),
+            destination: "0x0D1d9635D0640821d15e323ac8AdADfA9c111414".to_string(),
+            amount: "1".to_string(),
+            time: 1690393044548,
+        };
+        let user_signed_components = user_signed_components(Actions::UsdSend(usd_send))?;
+        let signature =
+            HyperliquidSigner::sign_hash(&user, user_signed_components.digest_to_sign).await?;
+        assert_eq!(
+            user_signed_components.verify_signer(&signature, user_address, &[])?,
+            user_address
+        );
+        let signature =
+            HyperliquidSigner::sign_hash(&agent, user_signed_components.digest_to_sign).await?;
+        assert!(user_signed_components
+            .verify_signer(&signature, user_address, &[agent_address])
+            .is_err());
+
+        // Flipping the flag does not let an agent sign a user-signed action
+        let flipped = UnsignedTransactionComponents {
+            is_l1_agent_signature: true,
+            ..user_signed_components
+        };
+        assert!(flipped.verify_digest().is_err());
+        assert!(flipped
+            .verify_signer(&signature, user_address, &[agent_address])
+            .is_err());
+        Ok(())
+    }
 }
@@ -1,6 +1,11 @@
 pub mod bridge;
 mod builder;
 mod components;
+mod multi_sig;
+mod summary;
 
 pub use builder::*;
+pub(crate) use components::user_signed_typed_data;
 pub use components::*;
+pub use multi_sig::*;
+pub use summary::*;
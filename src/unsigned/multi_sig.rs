@@ -0,0 +1,92 @@
+use std::collections::HashSet;
+
+use ethers::types::{Signature, H160, H256};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{prelude::*, signature::recover_signer, Actions, Error, MultiSigSigners};
+
+/// An action of a multi-sig user waiting for the signatures of its authorized users.
+///
+/// It serializes to JSON so it can be handed from signer to signer, each of them signing
+/// `digest_to_sign` and adding the result with `add_signature`. Once enough signatures are
+/// collected, `UnsignedTransactionBuilder::prepare_unsigned_multi_sig` wraps it into the
+/// `multiSig` action the outer signer signs and submits.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MultiSigTransaction {
+    pub multi_sig_user: H160,
+    pub outer_signer: H160,
+    /// The inner action, as prepared by `UnsignedTransactionBuilder`
+    pub action_payload_json: Value,
+    pub nonce: u64,
+    pub vault_address: Option<H160>,
+    #[serde(default)]
+    pub expires_after: Option<u64>,
+    pub is_mainnet: bool,
+    pub signatures: Vec<Signature>,
+}
+
+impl MultiSigTransaction {
+    pub(crate) fn inner_action(&self) -> Result<Actions> {
+        serde_json::from_value(self.action_payload_json.clone())
+            .map_err(|e| Error::JsonParse(e.to_string()))
+    }
+
+    /// The digest each authorized user signs
+    pub fn digest_to_sign(&self) -> Result<H256> {
+        self.inner_action()?.multi_sig_signing_digest(
+            self.multi_sig_user,
+            self.outer_signer,
+            self.nonce,
+            self.vault_address,
+            self.expires_after,
+            self.is_mainnet,
+        )
+    }
+
+    /// Adds a signature over `digest_to_sign`, returning the address that produced it
+    pub fn add_signature(&mut self, signature: Signature) -> Result<H160> {
+        let signer = recover_signer(self.digest_to_sign()?, &signature)?;
+        if self.signers()?.contains(&signer) {
+            return Err(Error::GenericRequest(format!(
+                "{signer:?} has already signed"
+            )));
+        }
+        self.signatures.push(signature);
+        Ok(signer)
+    }
+
+    /// Addresses that produced the collected signatures
+    pub fn signers(&self) -> Result<Vec<H160>> {
+        let digest = self.digest_to_sign()?;
+        self.signatures
+            .iter()
+            .map(|signature| recover_signer(digest, signature))
+            .collect()
+    }
+
+    /// Checks every collected signature comes from an authorized user and that the
+    /// threshold is reached by distinct signers
+    pub fn verify_signers(&self, signers: &MultiSigSigners) -> Result<()> {
+        let recovered = self.signers()?;
+        if let Some(&unauthorized) = recovered
+            .iter()
+            .find(|signer| !signers.authorized_users.contains(signer))
+        {
+            return Err(Error::UnauthorizedSigner {
+                user: self.multi_sig_user,
+                recovered: unauthorized,
+            });
+        }
+        // `signatures` is public and travels as JSON, so a repeated signature counts once
+        let distinct = recovered.iter().collect::<HashSet<_>>().len();
+        if distinct < signers.threshold {
+            return Err(Error::GenericRequest(format!(
+                "{distinct} of {} required signers collected",
+                signers.threshold
+            )));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,1018 @@
+use std::{collections::HashMap, fmt};
+
+use ethers::types::{H160, H256};
+use serde_json::Value;
+
+use crate::{
+    exchange::{BuilderInfo, OrderRequest},
+    prelude::*,
+    Actions, Error, MultiSigSigners, Order,
+};
+
+use super::components::UnsignedTransactionComponents;
+
+/// What a signing payload does, decoded for display in a clear-signing UI.
+///
+/// `recomputed_digest` is derived from the decoded action alone, so a UI that shows
+/// `details` only when `digest_matches` is set knows the summary describes exactly
+/// what the user is signing.
+#[derive(Debug, Clone)]
+pub struct SigningSummary {
+    pub action: Actions,
+    pub details: Vec<ActionDetail>,
+    /// "Mainnet" or "Testnet", when the digest matched. User-signed actions name it in their
+    /// `hyperliquidChain` field.
+    pub hyperliquid_chain: Option<String>,
+    pub vault_address: Option<H160>,
+    pub expires_after: Option<u64>,
+    pub recomputed_digest: H256,
+    pub digest_matches: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum OrderKind {
+    Limit {
+        tif: String,
+    },
+    Trigger {
+        trigger_px: String,
+        is_market: bool,
+        tpsl: String,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct OrderSummary {
+    pub coin: String,
+    pub is_buy: bool,
+    pub size: String,
+    pub limit_px: String,
+    pub kind: OrderKind,
+    pub reduce_only: bool,
+    pub cloid: Option<String>,
+    pub builder_fee: Option<BuilderInfo>,
+}
+
+/// One human-readable effect of an action, e.g. a single order of a bulk order
+#[derive(Debug, Clone)]
+pub enum ActionDetail {
+    Order(OrderSummary),
+    Cancel {
+        coin: String,
+        oid: u64,
+    },
+    CancelByCloid {
+        coin: String,
+        cloid: String,
+    },
+    Modify {
+        oid: u64,
+        order: OrderSummary,
+    },
+    TwapOrder {
+        coin: String,
+        is_buy: bool,
+        size: String,
+        reduce_only: bool,
+        minutes: u32,
+        randomize: bool,
+    },
+    TwapCancel {
+        coin: String,
+        twap_id: u64,
+    },
+    UpdateLeverage {
+        coin: String,
+        leverage: u32,
+        is_cross: bool,
+    },
+    UpdateIsolatedMargin {
+        coin: String,
+        /// USDC, negative when removing margin
+        amount: f64,
+    },
+    UsdSend {
+        destination: String,
+        amount: String,
+    },
+    Withdraw {
+        destination: String,
+        amount: String,
+    },
+    SpotSend {
+        destination: String,
+        token: String,
+        amount: String,
+    },
+    SendAsset {
+        destination: String,
+        /// `""` for the main perp dex, `"spot"` for the spot balance
+        source_dex: String,
+        destination_dex: String,
+        token: String,
+        amount: String,
+        from_sub_account: Option<String>,
+    },
+    StakingDeposit {
+        /// HYPE
+        amount: f64,
+    },
+    StakingWithdraw {
+        /// HYPE
+        amount: f64,
+    },
+    TokenDelegate {
+        validator: H160,
+        /// HYPE
+        amount: f64,
+        is_undelegate: bool,
+    },
+    ClassTransfer {
+        /// USDC
+        amount: f64,
+        to_perp: bool,
+    },
+    UsdClassTransfer {
+        /// USDC
+        amount: String,
+        to_perp: bool,
+        /// Vault or sub-account whose balances are moved
+        sub_account: Option<String>,
+    },
+    VaultTransfer {
+        vault_address: H160,
+        is_deposit: bool,
+        /// USDC
+        amount: f64,
+    },
+    ApproveAgent {
+        agent_address: H160,
+        agent_name: Option<String>,
+    },
+    ApproveBuilderFee {
+        builder: String,
+        max_fee_rate: String,
+    },
+    SetReferrer {
+        code: String,
+    },
+    CreateVault {
+        name: String,
+        description: String,
+        /// USDC
+        initial_amount: f64,
+    },
+    VaultModify {
+        vault_address: H160,
+        allow_deposits: Option<bool>,
+        always_close_on_withdraw: Option<bool>,
+    },
+    VaultDistribute {
+        vault_address: H160,
+        /// USDC
+        amount: f64,
+    },
+    CreateSubAccount {
+        name: String,
+    },
+    SubAccountTransfer {
+        sub_account_user: H160,
+        is_deposit: bool,
+        /// USDC
+        amount: f64,
+    },
+    SubAccountSpotTransfer {
+        sub_account_user: H160,
+        is_deposit: bool,
+        token: String,
+        amount: String,
+    },
+    ScheduleCancel {
+        /// None removes the scheduled cancel
+        time: Option<u64>,
+    },
+    ConvertToMultiSigUser {
+        /// None when the signers could not be parsed
+        signers: Option<MultiSigSigners>,
+    },
+    MultiSig {
+        multi_sig_user: H160,
+        outer_signer: H160,
+        signatures: usize,
+        inner: Vec<ActionDetail>,
+    },
+}
+
+impl SigningSummary {
+    /// Decodes an action payload and recomputes the digest it hashes to on the network
+    /// given by `is_mainnet`.
+    ///
+    /// Asset ids are resolved to coin names through `coin_to_asset`, falling back to
+    /// `asset <id>` for ids the mapping does not know.
+    pub fn decode(
+        action_payload_json: &Value,
+        nonce: u64,
+        vault_address: Option<H160>,
+        expires_after: Option<u64>,
+        is_mainnet: bool,
+        expected_digest: H256,
+        coin_to_asset: &HashMap<String, u32>,
+    ) -> Result<SigningSummary> {
+        let action: Actions = serde_json::from_value(action_payload_json.clone())
+            .map_err(|e| Error::JsonParse(e.to_string()))?;
+
+        let recomputed_digest =
+            action.signing_digest(nonce, vault_address, expires_after, is_mainnet)?;
+        let digest_matches = recomputed_digest == expected_digest;
+        // The digest of a user-signed action covers its own network name, not `is_mainnet`
+        let hyperliquid_chain = match action_payload_json.get("hyperliquidChain") {
+            Some(Value::String(chain)) if action.is_user_signed() => chain.clone(),
+            _ => if is_mainnet { "Mainnet" } else { "Testnet" }.to_string(),
+        };
+
+        let coins = coin_names(coin_to_asset);
+        let details = describe(&action, &coins);
+
+        Ok(SigningSummary {
+            action,
+            details,
+            hyperliquid_chain: digest_matches.then_some(hyperliquid_chain),
+            vault_address,
+            expires_after,
+            recomputed_digest,
+            digest_matches,
+        })
+    }
+}
+
+impl UnsignedTransactionComponents {
+    /// Decodes the components into a `SigningSummary` for display before signing
+    pub fn summarize(&self, coin_to_asset: &HashMap<String, u32>) -> Result<SigningSummary> {
+        SigningSummary::decode(
+            &self.action_payload_json,
+            self.nonce,
+            self.vault_address,
+            self.expires_after,
+            self.is_mainnet,
+            self.digest_to_sign,
+            coin_to_asset,
+        )
+    }
+}
+
+/// Inverts `coin_to_asset`, preferring pair names such as "PURR/USDC" over "@1" aliases
+fn coin_names(coin_to_asset: &HashMap<String, u32>) -> HashMap<u32, &str> {
+    let mut coins: HashMap<u32, &str> = HashMap::new();
+    for (coin, &asset) in coin_to_asset {
+        let keep_existing = coins.get(&asset).is_some_and(|existing| {
+            match (existing.starts_with('@'), coin.starts_with('@')) {
+                (false, true) => true,
+                (true, false) => false,
+                _ => *existing <= coin.as_str(),
+            }
+        });
+        if !keep_existing {
+            coins.insert(asset, coin);
+        }
+    }
+    coins
+}
+
+fn coin_name(coins: &HashMap<u32, &str>, asset: u32) -> String {
+    coins
+        .get(&asset)
+        .map(|coin| coin.to_string())
+        .unwrap_or_else(|| format!("asset {asset}"))
+}
+
+fn describe_order(
+    coins: &HashMap<u32, &str>,
+    order: &OrderRequest,
+    builder_fee: Option<&BuilderInfo>,
+) -> OrderSummary {
+    OrderSummary {
+        coin: coin_name(coins, order.asset),
+        is_buy: order.is_buy,
+        size: order.sz.clone(),
+        limit_px: order.limit_px.clone(),
+        kind: match &order.order_type {
+            Order::Limit(limit) => OrderKind::Limit {
+                tif: limit.tif.clone(),
+            },
+            Order::Trigger(trigger) => OrderKind::Trigger {
+                trigger_px: trigger.trigger_px.clone(),
+                is_market: trigger.is_market,
+                tpsl: trigger.tpsl.clone(),
+            },
+        },
+        reduce_only: order.reduce_only,
+        cloid: order.cloid.clone(),
+        builder_fee: builder_fee.cloned(),
+    }
+}
+
+fn describe(action: &Actions, coins: &HashMap<u32, &str>) -> Vec<ActionDetail> {
+    match action {
+        Actions::Order(bulk_order) => bulk_order
+            .orders
+            .iter()
+            .map(|order| {
+                ActionDetail::Order(describe_order(coins, order, bulk_order.builder.as_ref()))
+            })
+            .collect(),
+        Actions::Cancel(bulk_cancel) => bulk_cancel
+            .cancels
+            .iter()
+            .map(|cancel| ActionDetail::Cancel {
+                coin: coin_name(coins, cancel.asset),
+                oid: cancel.oid,
+            })
+            .collect(),
+        Actions::CancelByCloid(bulk_cancel) => bulk_cancel
+            .cancels
+            .iter()
+            .map(|cancel| ActionDetail::CancelByCloid {
+                coin: coin_name(coins, cancel.asset),
+                cloid: cancel.cloid.clone(),
+            })
+            .collect(),
+        Actions::BatchModify(bulk_modify) => bulk_modify
+            .modifies
+            .iter()
+            .map(|modify| ActionDetail::Modify {
+                oid: modify.oid,
+                order: describe_order(coins, &modify.order, None),
+            })
+            .collect(),
+        Actions::TwapOrder(twap_order) => vec![ActionDetail::TwapOrder {
+            coin: coin_name(coins, twap_order.twap.asset),
+            is_buy: twap_order.twap.is_buy,
+            size: twap_order.twap.sz.clone(),
+            reduce_only: twap_order.twap.reduce_only,
+            minutes: twap_order.twap.minutes,
+            randomize: twap_order.twap.randomize,
+        }],
+        Actions::TwapCancel(twap_cancel) => vec![ActionDetail::TwapCancel {
+            coin: coin_name(coins, twap_cancel.asset),
+            twap_id: twap_cancel.twap_id,
+        }],
+        Actions::UpdateLeverage(update) => vec![ActionDetail::UpdateLeverage {
+            coin: coin_name(coins, update.asset),
+            leverage: update.leverage,
+            is_cross: update.is_cross,
+        }],
+        Actions::UpdateIsolatedMargin(update) => vec![ActionDetail::UpdateIsolatedMargin {
+            coin: coin_name(coins, update.asset),
+            amount: update.ntli as f64 / 1_000_000.0,
+        }],
+        Actions::UsdSend(usd_send) => vec![ActionDetail::UsdSend {
+            destination: usd_send.destination.clone(),
+            amount: usd_send.amount.clone(),
+        }],
+        Actions::Withdraw3(withdraw) => vec![ActionDetail::Withdraw {
+            destination: withdraw.destination.clone(),
+            amount: withdraw.amount.clone(),
+        }],
+        Actions::SpotSend(spot_send) => vec![ActionDetail::SpotSend {
+            destination: spot_send.destination.clone(),
+            token: spot_send.token.clone(),
+            amount: spot_send.amount.clone(),
+        }],
+        Actions::SendAsset(send_asset) => vec![ActionDetail::SendAsset {
+            destination: send_asset.destination.clone(),
+            source_dex: send_asset.source_dex.clone(),
+            destination_dex: send_asset.destination_dex.clone(),
+            token: send_asset.token.clone(),
+            amount: send_asset.amount.clone(),
+            from_sub_account: Some(send_asset.from_sub_account.clone())
+                .filter(|sub_account| !sub_account.is_empty()),
+        }],
+        Actions::SpotUser(spot_user) => vec![ActionDetail::ClassTransfer {
+            amount: spot_user.class_transfer.usdc as f64 / 1_000_000.0,
+            to_perp: spot_user.class_transfer.to_perp,
+        }],
+        Actions::UsdClassTransfer(transfer) => {
+            let (amount, sub_account) = match transfer.amount.split_once(" subaccount:") {
+                Some((amount, sub_account)) => (amount, Some(sub_account.to_string())),
+                None => (transfer.amount.as_str(), None),
+            };
+            vec![ActionDetail::UsdClassTransfer {
+                amount: amount.to_string(),
+                to_perp: transfer.to_perp,
+                sub_account,
+            }]
+        }
+        Actions::CDeposit(deposit) => vec![ActionDetail::StakingDeposit {
+            amount: deposit.wei as f64 / 100_000_000.0,
+        }],
+        Actions::CWithdraw(withdraw) => vec![ActionDetail::StakingWithdraw {
+            amount: withdraw.wei as f64 / 100_000_000.0,
+        }],
+        Actions::TokenDelegate(delegate) => vec![ActionDetail::TokenDelegate {
+            validator: delegate.validator,
+            amount: delegate.wei as f64 / 100_000_000.0,
+            is_undelegate: delegate.is_undelegate,
+        }],
+        Actions::VaultTransfer(vault_transfer) => vec![ActionDetail::VaultTransfer {
+            vault_address: vault_transfer.vault_address,
+            is_deposit: vault_transfer.is_deposit,
+            amount: vault_transfer.usd as f64 / 1_000_000.0,
+        }],
+        Actions::ApproveAgent(approve_agent) => vec![ActionDetail::ApproveAgent {
+            agent_address: approve_agent.agent_address,
+            agent_name: approve_agent.agent_name.clone(),
+        }],
+        Actions::ApproveBuilderFee(approve_builder_fee) => vec![ActionDetail::ApproveBuilderFee {
+            builder: approve_builder_fee.builder.clone(),
+            max_fee_rate: approve_builder_fee.max_fee_rate.clone(),
+        }],
+        Actions::SetReferrer(set_referrer) => vec![ActionDetail::SetReferrer {
+            code: set_referrer.code.clone(),
+        }],
+        Actions::CreateVault(create) => vec![ActionDetail::CreateVault {
+            name: create.name.clone(),
+            description: create.description.clone(),
+            initial_amount: create.initial_usd as f64 / 1_000_000.0,
+        }],
+        Actions::VaultModify(modify) => vec![ActionDetail::VaultModify {
+            vault_address: modify.vault_address,
+            allow_deposits: modify.allow_deposits,
+            always_close_on_withdraw: modify.always_close_on_withdraw,
+        }],
+        Actions::VaultDistribute(distribute) => vec![ActionDetail::VaultDistribute {
+            vault_address: distribute.vault_address,
+            amount: distribute.usd as f64 / 1_000_000.0,
+        }],
+        Actions::CreateSubAccount(create) => vec![ActionDetail::CreateSubAccount {
+            name: create.name.clone(),
+        }],
+        Actions::SubAccountTransfer(transfer) => vec![ActionDetail::SubAccountTransfer {
+            sub_account_user: transfer.sub_account_user,
+            is_deposit: transfer.is_deposit,
+            amount: transfer.usd as f64 / 1_000_000.0,
+        }],
+        Actions::SubAccountSpotTransfer(transfer) => vec![ActionDetail::SubAccountSpotTransfer {
+            sub_account_user: transfer.sub_account_user,
+            is_deposit: transfer.is_deposit,
+            token: transfer.token.clone(),
+            amount: transfer.amount.clone(),
+        }],
+        Actions::ScheduleCancel(schedule_cancel) => vec![ActionDetail::ScheduleCancel {
+            time: schedule_cancel.time,
+        }],
+        Actions::ConvertToMultiSigUser(convert) => vec![ActionDetail::ConvertToMultiSigUser {
+            signers: serde_json::from_str(&convert.signers).ok(),
+        }],
+        Actions::MultiSig(multi_sig) => vec![ActionDetail::MultiSig {
+            multi_sig_user: multi_sig.payload.multi_sig_user,
+            outer_signer: multi_sig.payload.outer_signer,
+            signatures: multi_sig.signatures.len(),
+            inner: describe(&multi_sig.payload.action, coins),
+        }],
+    }
+}
+
+impl fmt::Display for OrderSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let side = if self.is_buy { "Buy" } else { "Sell" };
+        write!(f, "{side} {} {}", self.size, self.coin)?;
+        match &self.kind {
+            OrderKind::Limit { tif } => write!(f, " @ {} ({tif})", self.limit_px)?,
+            OrderKind::Trigger {
+                trigger_px,
+                is_market,
+                tpsl,
+            } => {
+                let tpsl = match tpsl.as_str() {
+                    "tp" => "take profit",
+                    "sl" => "stop loss",
+                    other => other,
+                };
+                write!(f, " {tpsl} triggered at {trigger_px}")?;
+                if *is_market {
+                    write!(f, ", market, limit {}", self.limit_px)?;
+                } else {
+                    write!(f, ", limit {}", self.limit_px)?;
+                }
+            }
+        }
+        if self.reduce_only {
+            write!(f, ", reduce-only")?;
+        }
+        if let Some(cloid) = &self.cloid {
+            write!(f, ", cloid {cloid}")?;
+        }
+        if let Some(builder) = &self.builder_fee {
+            // fee is in tenths of a basis point
+            write!(
+                f,
+                ", builder fee {}% to {}",
+                builder.fee as f64 / 1000.0,
+                builder.builder
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ActionDetail {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ActionDetail::Order(order) => write!(f, "{order}"),
+            ActionDetail::Cancel { coin, oid } => write!(f, "Cancel {coin} order {oid}"),
+            ActionDetail::CancelByCloid { coin, cloid } => {
+                write!(f, "Cancel {coin} order with cloid {cloid}")
+            }
+            ActionDetail::Modify { oid, order } => write!(f, "Modify order {oid} to: {order}"),
+            ActionDetail::TwapOrder {
+                coin,
+                is_buy,
+                size,
+                reduce_only,
+                minutes,
+                randomize,
+            } => {
+                let side = if *is_buy { "Buy" } else { "Sell" };
+                write!(f, "TWAP {side} {size} {coin} over {minutes} minutes")?;
+                if *randomize {
+                    write!(f, ", randomized")?;
+                }
+                if *reduce_only {
+                    write!(f, ", reduce-only")?;
+                }
+                Ok(())
+            }
+            ActionDetail::TwapCancel { coin, twap_id } => {
+                write!(f, "Cancel {coin} TWAP {twap_id}")
+            }
+            ActionDetail::UpdateLeverage {
+                coin,
+                leverage,
+                is_cross,
+            } => {
+                let mode = if *is_cross { "cross" } else { "isolated" };
+                write!(f, "Set {coin} leverage to {leverage}x {mode}")
+            }
+            ActionDetail::UpdateIsolatedMargin { coin, amount } => {
+                if *amount < 0.0 {
+                    write!(f, "Remove {} USDC of {coin} isolated margin", -amount)
+                } else {
+                    write!(f, "Add {amount} USDC of {coin} isolated margin")
+                }
+            }
+            ActionDetail::UsdSend {
+                destination,
+                amount,
+            } => write!(f, "Send {amount} USDC to {destination}"),
+            ActionDetail::Withdraw {
+                destination,
+                amount,
+            } => write!(f, "Withdraw {amount} USDC to {destination} on Arbitrum"),
+            ActionDetail::SpotSend {
+                destination,
+                token,
+                amount,
+            } => write!(f, "Send {amount} {token} to {destination}"),
+            ActionDetail::SendAsset {
+                destination,
+                source_dex,
+                destination_dex,
+                token,
+                amount,
+                from_sub_account,
+            } => {
+                let dex_name = |dex: &str| match dex {
+                    "" => "perp".to_string(),
+                    "spot" => "spot".to_string(),
+                    dex => format!("{dex} perp"),
+                };
+                write!(
+                    f,
+                    "Send {amount} {token} from {} to the {} balance of {destination}",
+                    dex_name(source_dex),
+                    dex_name(destination_dex)
+                )?;
+                if let Some(from_sub_account) = from_sub_account {
+                    write!(f, ", from {from_sub_account}")?;
+                }
+                Ok(())
+            }
+            ActionDetail::StakingDeposit { amount } => write!(f, "Stake {amount} HYPE"),
+            ActionDetail::StakingWithdraw { amount } => {
+                write!(f, "Unstake {amount} HYPE to the spot balance")
+            }
+            ActionDetail::TokenDelegate {
+                validator,
+                amount,
+                is_undelegate,
+            } => {
+                if *is_undelegate {
+                    write!(f, "Undelegate {amount} HYPE from validator {validator:?}")
+                } else {
+                    write!(f, "Delegate {amount} HYPE to validator {validator:?}")
+                }
+            }
+            ActionDetail::ClassTransfer { amount, to_perp } => {
+                let (from, to) = if *to_perp {
+                    ("spot", "perp")
+                } else {
+                    ("perp", "spot")
+                };
+                write!(f, "Move {amount} USDC from {from} to {to}")
+            }
+            ActionDetail::UsdClassTransfer {
+                amount,
+                to_perp,
+                sub_account,
+            } => {
+                let (from, to) = if *to_perp {
+                    ("spot", "perp")
+                } else {
+                    ("perp", "spot")
+                };
+                write!(f, "Move {amount} USDC from {from} to {to}")?;
+                if let Some(sub_account) = sub_account {
+                    write!(f, " of {sub_account}")?;
+                }
+                Ok(())
+            }
+            ActionDetail::VaultTransfer {
+                vault_address,
+                is_deposit,
+                amount,
+            } => {
+                if *is_deposit {
+                    write!(f, "Deposit {amount} USDC into vault {vault_address:?}")
+                } else {
+                    write!(f, "Withdraw {amount} USDC from vault {vault_address:?}")
+                }
+            }
+            ActionDetail::ApproveAgent {
+                agent_address,
+                agent_name,
+            } => {
+                write!(f, "Approve agent {agent_address:?}")?;
+                if let Some(name) = agent_name {
+                    write!(f, " named {name}")?;
+                }
+                Ok(())
+            }
+            ActionDetail::ApproveBuilderFee {
+                builder,
+                max_fee_rate,
+            } => write!(
+                f,
+                "Approve builder {builder} to charge up to {max_fee_rate}"
+            ),
+            ActionDetail::SetReferrer { code } => write!(f, "Set referral code {code}"),
+            ActionDetail::CreateVault {
+                name,
+                description,
+                initial_amount,
+            } => write!(
+                f,
+                "Create vault {name} ({description}) with {initial_amount} USDC"
+            ),
+            ActionDetail::VaultModify {
+                vault_address,
+                allow_deposits,
+                always_close_on_withdraw,
+            } => {
+                write!(f, "Modify vault {vault_address:?}:")?;
+                if let Some(allow_deposits) = allow_deposits {
+                    write!(f, " allow deposits {allow_deposits}")?;
+                }
+                if let Some(always_close) = always_close_on_withdraw {
+                    write!(f, " always close on withdraw {always_close}")?;
+                }
+                Ok(())
+            }
+            ActionDetail::VaultDistribute {
+                vault_address,
+                amount,
+            } => write!(
+                f,
+                "Distribute {amount} USDC of vault {vault_address:?} to its followers"
+            ),
+            ActionDetail::CreateSubAccount { name } => write!(f, "Create sub-account {name}"),
+            ActionDetail::SubAccountTransfer {
+                sub_account_user,
+                is_deposit,
+                amount,
+            } => {
+                if *is_deposit {
+                    write!(
+                        f,
+                        "Transfer {amount} USDC to sub-account {sub_account_user:?}"
+                    )
+                } else {
+                    write!(
+                        f,
+                        "Transfer {amount} USDC from sub-account {sub_account_user:?}"
+                    )
+                }
+            }
+            ActionDetail::SubAccountSpotTransfer {
+                sub_account_user,
+                is_deposit,
+                token,
+                amount,
+            } => {
+                if *is_deposit {
+                    write!(
+                        f,
+                        "Transfer {amount} {token} to sub-account {sub_account_user:?}"
+                    )
+                } else {
+                    write!(
+                        f,
+                        "Transfer {amount} {token} from sub-account {sub_account_user:?}"
+                    )
+                }
+            }
+            ActionDetail::ScheduleCancel { time } => match time {
+                Some(time) => write!(f, "Cancel all open orders at {time}"),
+                None => write!(f, "Remove the scheduled cancel of all open orders"),
+            },
+            ActionDetail::ConvertToMultiSigUser { signers } => match signers {
+                Some(signers) => {
+                    write!(
+                        f,
+                        "Convert to a multi-sig user requiring {} of",
+                        signers.threshold
+                    )?;
+                    for user in &signers.authorized_users {
+                        write!(f, " {user:?}")?;
+                    }
+                    Ok(())
+                }
+                None => write!(f, "Convert to a multi-sig user with unreadable signers"),
+            },
+            ActionDetail::MultiSig {
+                multi_sig_user,
+                outer_signer,
+                signatures,
+                inner,
+            } => {
+                write!(
+                    f,
+                    "As multi-sig user {multi_sig_user:?}, submitted by {outer_signer:?} with {signatures} signatures:"
+                )?;
+                for detail in inner {
+                    write!(f, "\n  {detail}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl fmt::Display for SigningSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for detail in &self.details {
+            writeln!(f, "{detail}")?;
+        }
+        if let Some(vault_address) = self.vault_address {
+            writeln!(f, "On behalf of vault {vault_address:?}")?;
+        }
+        if let Some(chain) = &self.hyperliquid_chain {
+            write!(f, "{chain}, digest {:?}", self.recomputed_digest)
+        } else {
+            write!(
+                f,
+                "WARNING: digest does not match the action, recomputed {:?}",
+                self.recomputed_digest
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ClientLimit, ClientOrder, ClientOrderRequest, UnsignedTransactionBuilder};
+
+    fn coin_to_asset() -> HashMap<String, u32> {
+        HashMap::from([
+            ("ETH".to_string(), 1),
+            ("PURR/USDC".to_string(), 10000),
+            ("@0".to_string(), 10000),
+        ])
+    }
+
+    fn components(action: Value, is_mainnet: bool) -> UnsignedTransactionComponents {
+        let action: Actions = serde_json::from_value(action).unwrap();
+        let nonce = 1583838;
+        UnsignedTransactionComponents {
+            digest_to_sign: action
+                .signing_digest(nonce, None, None, is_mainnet)
+                .unwrap(),
+            action_payload_json: serde_json::to_value(&action).unwrap(),
+            nonce,
+            vault_address: None,
+            expires_after: None,
+            eip712_domain_chain_id: None,
+            eip712_hyperliquid_chain_name: None,
+            is_l1_agent_signature: !action.is_user_signed(),
+            is_mainnet,
+        }
+    }
+
+    #[test]
+    fn test_summarize_order() {
+        let components = components(
+            serde_json::json!({
+                "type": "order",
+                "orders": [
+                    {"a": 1, "b": true, "p": "2000", "s": "0.5", "r": false, "t": {"limit": {"tif": "Gtc"}}},
+                    {"a": 10000, "b": false, "p": "0.1", "s": "100", "r": true,
+                     "t": {"trigger": {"isMarket": true, "triggerPx": "0.11", "tpsl": "tp"}}},
+                ],
+                "grouping": "na",
+                "builder": {"b": "0xf5bc9107916b91a3ea5966cd2e51655d21b7eb02", "f": 10},
+            }),
+            false,
+        );
+
+        let summary = components.summarize(&coin_to_asset()).unwrap();
+        assert!(summary.digest_matches);
+        assert_eq!(summary.hyperliquid_chain.as_deref(), Some("Testnet"));
+        assert_eq!(summary.details.len(), 2);
+        assert_eq!(
+            summary.details[0].to_string(),
+            "Buy 0.5 ETH @ 2000 (Gtc), builder fee 0.01% to 0xf5bc9107916b91a3ea5966cd2e51655d21b7eb02"
+        );
+        let ActionDetail::Order(order) = &summary.details[1] else {
+            panic!("expected an order");
+        };
+        assert_eq!(order.coin, "PURR/USDC");
+        assert!(order.reduce_only);
+        assert_eq!(
+            order.kind,
+            OrderKind::Trigger {
+                trigger_px: "0.11".to_string(),
+                is_market: true,
+                tpsl: "tp".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_summarize_transfer() {
+        let components = components(
+            serde_json::json!({
+                "type": "usdSend",
+                "signatureChainId": "0xa4b1",
+                "hyperliquidChain": "Mainnet",
+                "destination": "0x0d1d9635d0640821d15e323ac8adadfa9c111414",
+                "amount": "12.5",
+                "time": 1583838,
+            }),
+            true,
+        );
+
+        let summary = components.summarize(&coin_to_asset()).unwrap();
+        assert!(summary.digest_matches);
+        assert_eq!(summary.hyperliquid_chain.as_deref(), Some("Mainnet"));
+        assert_eq!(
+            summary.details[0].to_string(),
+            "Send 12.5 USDC to 0x0d1d9635d0640821d15e323ac8adadfa9c111414"
+        );
+    }
+
+    #[test]
+    fn test_summarize_testnet_withdraw() {
+        let components = components(
+            serde_json::json!({
+                "type": "withdraw3",
+                "signatureChainId": "0x66eee",
+                "hyperliquidChain": "Testnet",
+                "destination": "0x0d1d9635d0640821d15e323ac8adadfa9c111414",
+                "amount": "5",
+                "time": 1583838,
+            }),
+            false,
+        );
+
+        let summary = components.summarize(&coin_to_asset()).unwrap();
+        assert!(summary.digest_matches);
+        assert_eq!(summary.hyperliquid_chain.as_deref(), Some("Testnet"));
+    }
+
+    #[test]
+    fn test_summarize_class_transfer() {
+        let components = components(
+            serde_json::json!({
+                "type": "usdClassTransfer",
+                "signatureChainId": "0xa4b1",
+                "hyperliquidChain": "Mainnet",
+                "amount": "2.5 subaccount:0x0d1d9635d0640821d15e323ac8adadfa9c111414",
+                "toPerp": false,
+                "nonce": 1583838,
+            }),
+            true,
+        );
+
+        let summary = components.summarize(&coin_to_asset()).unwrap();
+        assert!(summary.digest_matches);
+        assert_eq!(
+            summary.details[0].to_string(),
+            "Move 2.5 USDC from perp to spot of 0x0d1d9635d0640821d15e323ac8adadfa9c111414"
+        );
+    }
+
+    #[test]
+    fn test_summarize_send_asset() {
+        let components = components(
+            serde_json::json!({
+                "type": "sendAsset",
+                "signatureChainId": "0xa4b1",
+                "hyperliquidChain": "Mainnet",
+                "destination": "0x0d1d9635d0640821d15e323ac8adadfa9c111414",
+                "sourceDex": "",
+                "destinationDex": "test",
+                "token": "USDC",
+                "amount": "10",
+                "fromSubAccount": "",
+                "nonce": 1583838,
+            }),
+            true,
+        );
+
+        let summary = components.summarize(&coin_to_asset()).unwrap();
+        assert!(summary.digest_matches);
+        assert_eq!(
+            summary.details[0].to_string(),
+            "Send 10 USDC from perp to the test perp balance of 0x0d1d9635d0640821d15e323ac8adadfa9c111414"
+        );
+    }
+
+    #[test]
+    fn test_summarize_detects_tampering() {
+        let mut components = components(
+            serde_json::json!({
+                "type": "updateLeverage",
+                "asset": 7,
+                "isCross": true,
+                "leverage": 5,
+            }),
+            true,
+        );
+        components.action_payload_json["leverage"] = 50.into();
+
+        let summary = components.summarize(&coin_to_asset()).unwrap();
+        assert!(!summary.digest_matches);
+        assert_eq!(summary.hyperliquid_chain, None);
+        assert_eq!(
+            summary.details[0].to_string(),
+            "Set asset 7 leverage to 50x cross"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_summarize_prepared_order() {
+        let snapshot = serde_json::from_value(serde_json::json!({
+            "perpMeta": [{
+                "dex": null,
+                "meta": {
+                    "universe": [{"name": "ETH", "szDecimals": 4, "maxLeverage": 25, "marginTableId": 25}],
+                    "marginTables": [],
+                    "collateralToken": 0,
+                },
+            }],
+            "spotMeta": {"universe": [], "tokens": []},
+        }))
+        .unwrap();
+        let builder = UnsignedTransactionBuilder::from_snapshot(
+            None,
+            Some(crate::BaseUrl::Testnet),
+            snapshot,
+            None,
+        );
+
+        let components = builder
+            .prepare_unsigned_order(
+                ClientOrderRequest {
+                    asset: "ETH".to_string(),
+                    is_buy: false,
+                    reduce_only: true,
+                    limit_px: 1800.0,
+                    sz: 0.25,
+                    cloid: None,
+                    order_type: ClientOrder::Limit(ClientLimit {
+                        tif: "Ioc".to_string(),
+                    }),
+                },
+                None,
+            )
+            .await
+            .unwrap();
+
+        let summary = builder.summarize(&components).unwrap();
+        assert!(summary.digest_matches);
+        assert_eq!(
+            summary.details[0].to_string(),
+            "Sell 0.25 ETH @ 1800 (Ioc), reduce-only"
+        );
+    }
+}
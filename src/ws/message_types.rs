@@ -46,6 +46,16 @@ pub struct UserNonFundingLedgerUpdates {
     pub data: UserNonFundingLedgerUpdatesData,
 }
 
+#[derive(Deserialize, Clone, Debug)]
+pub struct UserTwapSliceFills {
+    pub data: UserTwapSliceFillsData,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct UserTwapHistory {
+    pub data: UserTwapHistoryData,
+}
+
 #[derive(Deserialize, Clone, Debug)]
 pub struct Notification {
     pub data: NotificationData,
@@ -272,6 +272,60 @@ pub struct SpotGenesis {
     pub amount: String,
 }
 
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UserTwapSliceFillsData {
+    pub is_snapshot: Option<bool>,
+    pub user: H160,
+    pub twap_slice_fills: Vec<TwapSliceFill>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TwapSliceFill {
+    pub fill: TradeInfo,
+    pub twap_id: u64,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UserTwapHistoryData {
+    pub is_snapshot: Option<bool>,
+    pub user: H160,
+    pub history: Vec<TwapHistory>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TwapHistory {
+    pub state: TwapState,
+    pub status: TwapStatus,
+    pub time: u64,
+    pub twap_id: Option<u64>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TwapState {
+    pub coin: String,
+    pub user: H160,
+    pub side: String,
+    pub sz: String,
+    pub executed_sz: String,
+    pub executed_ntl: String,
+    pub minutes: u32,
+    pub reduce_only: bool,
+    pub randomize: bool,
+    pub timestamp: u64,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+pub struct TwapStatus {
+    /// "activated", "terminated", "finished" or "error"
+    pub status: String,
+    pub description: Option<String>,
+}
+
 #[derive(Deserialize, Clone, Debug)]
 pub struct NotificationData {
     pub notification: String,
@@ -2,7 +2,7 @@ use crate::{
     prelude::*,
     ws::message_types::{AllMids, Bbo, Candle, L2Book, OrderUpdates, Trades, User},
     ActiveAssetCtx, ActiveAssetData, Error, Notification, UserFills, UserFundings,
-    UserNonFundingLedgerUpdates, WebData2,
+    UserNonFundingLedgerUpdates, UserTwapHistory, UserTwapSliceFills, WebData2,
 };
 use futures_util::{stream::SplitSink, SinkExt, StreamExt};
 use log::{error, info, warn};
@@ -63,6 +63,8 @@ pub enum Subscription {
     UserFills { user: H160 },
     UserFundings { user: H160 },
     UserNonFundingLedgerUpdates { user: H160 },
+    UserTwapSliceFills { user: H160 },
+    UserTwapHistory { user: H160 },
     ActiveAssetCtx { coin: String },
     ActiveAssetData { user: H160, coin: String },
     Bbo { coin: String },
@@ -84,6 +86,8 @@ pub enum Message {
     OrderUpdates(OrderUpdates),
     UserFundings(UserFundings),
     UserNonFundingLedgerUpdates(UserNonFundingLedgerUpdates),
+    UserTwapSliceFills(UserTwapSliceFills),
+    UserTwapHistory(UserTwapHistory),
     Notification(Notification),
     WebData2(WebData2),
     ActiveAssetCtx(ActiveAssetCtx),
@@ -263,6 +267,18 @@ impl WsManager {
                 })
                 .map_err(|e| Error::JsonParse(e.to_string()))
             }
+            Message::UserTwapSliceFills(slice_fills) => {
+                serde_json::to_string(&Subscription::UserTwapSliceFills {
+                    user: slice_fills.data.user,
+                })
+                .map_err(|e| Error::JsonParse(e.to_string()))
+            }
+            Message::UserTwapHistory(history) => {
+                serde_json::to_string(&Subscription::UserTwapHistory {
+                    user: history.data.user,
+                })
+                .map_err(|e| Error::JsonParse(e.to_string()))
+            }
             Message::Notification(_) => Ok("notification".to_string()),
             Message::WebData2(web_data2) => serde_json::to_string(&Subscription::WebData2 {
                 user: web_data2.data.user,